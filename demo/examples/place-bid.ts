@@ -150,13 +150,13 @@ async function placeBidScript() {
         
       } catch (error: any) {
         lastError = error;
-        // Check for BidNotAtCurrentPrice error (6004) in different ways
+        // Check for CurrentPriceAboveMax error (6004) in different ways
         const errorMessage = error.message || '';
         const hasContext = error.context && error.context.code === 6004;
         const isBidPriceError = errorMessage.includes('#6004') || hasContext;
         
         if (isBidPriceError) {
-          // BidNotAtCurrentPrice error - try next offset
+          // CurrentPriceAboveMax error - try next offset
           if (offset > -2) {
             continue;
           } else {
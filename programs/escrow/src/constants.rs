@@ -5,19 +5,44 @@ pub const EVENT_STATUS_CREATED: u8 = 0;
 pub const EVENT_STATUS_ACTIVE: u8 = 1;
 pub const EVENT_STATUS_FINALIZED: u8 = 2;
 pub const EVENT_STATUS_CANCELLED: u8 = 3;
+pub const EVENT_STATUS_FAILED: u8 = 4; // Minimum ticket sales not reached, all bids refundable
 
 pub const BID_STATUS_PENDING: u8 = 0;
 pub const BID_STATUS_AWARDED: u8 = 1;
 pub const BID_STATUS_REFUNDED: u8 = 2;
+pub const BID_STATUS_COMMITTED: u8 = 3; // Sealed bid committed but not yet revealed
+pub const BID_STATUS_WAITLISTED: u8 = 4; // Placed after its tier sold out, waiting for a ticket to be released
+pub const BID_STATUS_LIMIT: u8 = 5; // Standing limit bid, waiting for the price to fall to its limit
+
+pub const AUCTION_TYPE_DUTCH: u8 = 0;
+pub const AUCTION_TYPE_SEALED: u8 = 1; // Commit-reveal, uniform clearing price
+
+pub const AUCTION_PHASE_NOT_STARTED: u8 = 0;
+pub const AUCTION_PHASE_COMMIT: u8 = 1; // Dutch auctions take bids in this phase
+pub const AUCTION_PHASE_REVEAL: u8 = 2; // Sealed-bid auctions only
+pub const AUCTION_PHASE_CLOSED: u8 = 3;
 
 pub const TICKET_STATUS_OWNED: u8 = 0;
 pub const TICKET_STATUS_CLAIMED: u8 = 1;
 pub const TICKET_STATUS_REFUNDED: u8 = 2;
 
+pub const TRANSFER_POLICY_NONE: u8 = 0; // Tickets stay with the wallet they were issued to
+pub const TRANSFER_POLICY_FREE: u8 = 1; // Tickets can be transferred or resold at any price
+pub const TRANSFER_POLICY_CAPPED: u8 = 2; // Resale price capped at a share of the ticket tier's close price
+
 // Auction parameter constants
 pub const MAX_METADATA_URL_LEN: usize = 200;
 pub const MAX_TICKETS_PER_EVENT: u32 = 1000;
 pub const MAX_TICKETS_TEST_MODE: u32 = 10; // Reduced for tests
+pub const MAX_TICKETS_PER_BID: u8 = 8; // Families and groups bid for several tickets at once
+pub const MAX_EVENT_HOLDINGS_PER_USER: usize = 16; // Events a user account tracks holdings for
+pub const MAX_TICKET_TIERS: usize = 4; // e.g. GA, VIP, balcony
+pub const MAX_PAYEES: usize = 8; // e.g. promoter, venue, artist
+pub const TOTAL_BASIS_POINTS: u16 = 10_000; // Payee shares must add up to 100%
+pub const MAX_RESALE_CAP_BPS: u16 = 20_000; // Capped resales can go up to twice the ticket tier's close price
+pub const TICKETS_PER_INVENTORY_PAGE: usize = 256; // Keeps each inventory page under the 10KB CPI allocation limit
+pub const MAX_ASSETS_PER_REGISTRATION: usize = 24; // Asset IDs per register_ticket_assets call, so each chunk fits in one transaction
+pub const MAX_AWARD_BATCH_BIDS: usize = 24; // Bids per award_tickets_batch call, so the skipped-bid report fits in return data
 
 // Time constants (in seconds)
 pub const MIN_AUCTION_DURATION: i64 = 300; // 5 minutes
@@ -10,6 +10,130 @@ pub enum ErrorCode {
     AuctionNotStarted,
     #[msg("Auction has already ended.")]
     AuctionEnded,
-    #[msg("Bid must be exactly equal to the current auction price.")]
-    BidNotAtCurrentPrice,
+    #[msg("Maximum bid price is below the current auction price.")]
+    CurrentPriceAboveMax,
+    #[msg("Minimum tickets sold cannot exceed the ticket supply.")]
+    MinTicketsExceedsSupply,
+    #[msg("Payment mint and token accounts are required for token-denominated events.")]
+    PaymentAccountsMissing,
+    #[msg("Payment mint does not match the event.")]
+    PaymentMintMismatch,
+    #[msg("Auction has not been finalized.")]
+    AuctionNotFinalized,
+    #[msg("Proceeds have already been withdrawn.")]
+    ProceedsAlreadyWithdrawn,
+    #[msg("Bid quantity must be between 1 and the maximum tickets per bid.")]
+    InvalidBidQuantity,
+    #[msg("Cannot award more tickets than the bid is for.")]
+    AwardQuantityExceedsBid,
+    #[msg("This purchase would exceed the event's per-wallet ticket cap.")]
+    WalletTicketCapExceeded,
+    #[msg("A user account is required for events with a per-wallet ticket cap.")]
+    UserAccountRequired,
+    #[msg("User account cannot track holdings for any more events.")]
+    UserHoldingsFull,
+    #[msg("Ticket tier does not exist for this event.")]
+    InvalidTicketTier,
+    #[msg("Event already has the maximum number of ticket tiers.")]
+    TooManyTicketTiers,
+    #[msg("A close price must be provided for every additional ticket tier.")]
+    TierClosePricesMismatch,
+    #[msg("Metadata URL is too long.")]
+    MetadataUrlTooLong,
+    #[msg("Event can only be changed before it is activated.")]
+    EventAlreadyActivated,
+    #[msg("Event is not set to auto-activate or its auction has not started yet.")]
+    EventNotDueForActivation,
+    #[msg("Auction extension settings must not be negative or exceed the maximum auction duration.")]
+    InvalidExtensionConfig,
+    #[msg("Inventory page is out of range for the event's ticket supply.")]
+    InvalidInventoryPage,
+    #[msg("Asset IDs must be registered in order, in chunks that stay within one inventory page and the ticket supply.")]
+    InvalidAssetRegistration,
+    #[msg("Not enough cNFT asset IDs have been registered to award these tickets.")]
+    TicketAssetsNotRegistered,
+    #[msg("Payees must be unique, have non-zero shares adding up to 10,000 basis points, and include the dust payee.")]
+    InvalidPayeeShares,
+    #[msg("This event's proceeds are split between payees; use distribute_proceeds instead.")]
+    ProceedsSplitBetweenPayees,
+    #[msg("Payee accounts must be passed in the same order as the event's payee table.")]
+    PayeeAccountMismatch,
+    #[msg("Bid still has funds owed to the bidder and can't be closed.")]
+    BidNotSettled,
+    #[msg("Event still has open bids or unpaid proceeds and can't be closed.")]
+    EventNotSettled,
+    #[msg("Bids for this event can only be settled by the bidder; uniform-price settlement is not enabled.")]
+    UniformPriceNotEnabled,
+    #[msg("This instruction isn't available for the event's auction type.")]
+    WrongAuctionType,
+    #[msg("This instruction isn't allowed in the sealed-bid auction's current phase.")]
+    SealedBidPhaseMismatch,
+    #[msg("Revealed amount and salt don't match the bid's commitment.")]
+    CommitmentMismatch,
+    #[msg("Sealed-bid auctions need a single tier, a reveal window after the commit window, a deposit of at least the minimum price, and a slash of at most 10,000 basis points.")]
+    InvalidSealedBidConfig,
+    #[msg("Randomness account isn't a valid, freshly committed Switchboard randomness account.")]
+    InvalidRandomnessAccount,
+    #[msg("Randomness hasn't been revealed yet.")]
+    RandomnessNotResolved,
+    #[msg("Every pending bid in the lottery pool must be passed to draw_winners exactly once.")]
+    LotteryEntriesMismatch,
+    #[msg("Bid wasn't drawn as a lottery winner, or the lottery hasn't been drawn yet.")]
+    NotLotteryWinner,
+    #[msg("A ticket has already been issued to this bidder for this event.")]
+    TicketAlreadyIssued,
+    #[msg("Batch accounts must be (bid, ticket) pairs, or (bid, ticket, user) triples when tickets are capped per wallet, for this event's bids.")]
+    InvalidAwardBatch,
+    #[msg("This event awards tickets in bid order; use award_next_ticket instead.")]
+    SequentialAwardsRequired,
+    #[msg("Sequential awards aren't enabled for this event.")]
+    SequentialAwardsNotEnabled,
+    #[msg("Bid isn't next in sequence to be awarded.")]
+    BidOutOfSequence,
+    #[msg("Bid can still be awarded, so it can't be skipped.")]
+    BidStillAwardable,
+    #[msg("No bids are waiting for a ticket in this tier.")]
+    WaitlistEmpty,
+    #[msg("Only the bid at the head of the tier's waitlist can be promoted.")]
+    WaitlistOutOfOrder,
+    #[msg("Waitlisted bid is for fewer tickets than the ticket being released.")]
+    WaitlistBidTooSmall,
+    #[msg("Not enough tickets are left for this purchase.")]
+    TicketsSoldOut,
+    #[msg("Instant-buy events need the ticket and inventory page accounts to issue the ticket.")]
+    TicketAccountsMissing,
+    #[msg("Instant-buy and sequential awards can't both be enabled.")]
+    ConflictingAwardModes,
+    #[msg("Limit price must be below the current auction price and no lower than the tier's end price.")]
+    InvalidLimitPrice,
+    #[msg("Bid isn't a standing limit bid.")]
+    NotLimitBid,
+    #[msg("Only pending and limit bids can be withdrawn, and only while the auction is active.")]
+    BidNotWithdrawable,
+    #[msg("Award processing has started, so bids can no longer be withdrawn.")]
+    WithdrawalsClosed,
+    #[msg("Withdrawal fee can be at most 10,000 basis points.")]
+    InvalidWithdrawalPolicy,
+    #[msg("Account doesn't belong to this event or signer.")]
+    AccountRelationshipMismatch,
+    #[msg("Event tickets can't be transferred.")]
+    TicketNotTransferable,
+    #[msg("Resale price is above the event's resale cap.")]
+    ResalePriceAboveCap,
+    #[msg("Invalid transfer policy, resale cap out of range, or royalty above 10,000 basis points.")]
+    InvalidTransferPolicy,
+    #[msg("Listing price is above the most the buyer agreed to pay.")]
+    ListingPriceAboveMax,
+    #[msg("Close price is above what an awarded bid paid per ticket.")]
+    ClosePriceAboveWinningBid,
+    #[msg("Ticket supply is below the tickets already registered or awarded.")]
+    SupplyBelowIssuedTickets,
+    #[msg("Event has tickets listed for resale, so it can't be closed.")]
+    TicketsOutstanding,
+    #[msg("Every revealed bid must be passed to rank_sealed_bids exactly once.")]
+    SealedBidEntriesMismatch,
+    #[msg("Bid wasn't ranked as a sealed-bid winner, or the bids haven't been ranked yet.")]
+    NotSealedBidWinner,
+    #[msg("Ticket has changed hands since it was issued, so it can't be released.")]
+    TicketChangedHands,
 }
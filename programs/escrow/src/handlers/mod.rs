@@ -21,3 +21,21 @@ pub use ticketfair_user::*;
 
 pub mod ticketfair_bid;
 pub use ticketfair_bid::*;
+
+pub mod ticketfair_inventory;
+pub use ticketfair_inventory::*;
+
+pub mod ticketfair_sealed_bid;
+pub use ticketfair_sealed_bid::*;
+
+pub mod ticketfair_lottery;
+pub use ticketfair_lottery::*;
+
+pub mod ticketfair_waitlist;
+pub use ticketfair_waitlist::*;
+
+pub mod ticketfair_limit_bid;
+pub use ticketfair_limit_bid::*;
+
+pub mod ticketfair_marketplace;
+pub use ticketfair_marketplace::*;
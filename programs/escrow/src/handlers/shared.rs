@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 
 use anchor_spl::token_interface::{
     close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
@@ -66,6 +67,37 @@ pub fn close_token_account<'info>(
     })
 }
 
+// Transfer lamports from one system account to another
+// If transferring from a PDA (e.g. the Ticketfair escrow), owning_pda_seeds must be provided.
+pub fn transfer_lamports<'info>(
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    system_program: &Program<'info, System>,
+    owning_pda_seeds: Option<&[&[u8]]>,
+) -> Result<()> {
+    let transfer_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+    };
+
+    // Only one signer seed (the PDA that holds the lamports) is needed, so we create an array with the seeds
+    let signers_seeds = owning_pda_seeds.map(|seeds| [seeds]);
+
+    transfer(
+        if let Some(seeds_arr) = signers_seeds.as_ref() {
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                transfer_accounts,
+                seeds_arr,
+            )
+        } else {
+            CpiContext::new(system_program.to_account_info(), transfer_accounts)
+        },
+        amount,
+    )
+}
+
 /// Derive the event PDA for a given organizer pubkey
 pub fn derive_event_pda(organizer: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[b"event", organizer.as_ref()], program_id)
@@ -1,7 +1,10 @@
 //! Ticketfair bid instruction handlers (Dutch Auction)
 
 use anchor_lang::prelude::*;
-use crate::state::{Bid, Event, Ticket};
+use anchor_lang::system_program::{create_account, CreateAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use super::shared::{transfer_lamports, transfer_tokens};
+use crate::state::{Bid, Event, SkippedBid, Ticket, TicketInventoryPage, User};
 use crate::constants::*;
 use crate::error::ErrorCode;
 
@@ -13,7 +16,11 @@ use crate::error::ErrorCode;
 pub struct PlaceBidAccountConstraints<'info> {
     #[account(mut)]
     pub bidder: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
     pub event: Account<'info, Event>,
     /// The PDA that will hold escrowed funds for the event
     /// Seeds: [b"escrow", event.key().as_ref()]
@@ -27,12 +34,68 @@ pub struct PlaceBidAccountConstraints<'info> {
         bump
     )]
     pub bid: Account<'info, Bid>,
+    /// Bidder's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", bidder.key().as_ref()], bump = user.bump)]
+    pub user: Option<Account<'info, User>>,
+    /// Ticket issued straight to the bidder (instant-buy events only)
+    #[account(
+        init,
+        payer = bidder,
+        space = Ticket::DISCRIMINATOR.len() + Ticket::INIT_SPACE,
+        seeds = [b"ticket", event.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub ticket: Option<Account<'info, Ticket>>,
+    /// Inventory page holding the next unawarded asset (instant-buy events only)
+    #[account(
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            TicketInventoryPage::locate(event.assets_awarded).0.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inventory_page: Option<AccountLoader<'info, TicketInventoryPage>>,
+    /// Following inventory page (only needed when the purchase runs past the end of inventory_page)
+    #[account(
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            (TicketInventoryPage::locate(event.assets_awarded).0 + 1).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub next_inventory_page: Option<AccountLoader<'info, TicketInventoryPage>>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn place_bid(
     context: Context<PlaceBidAccountConstraints>,
-    amount: u64,
+    max_price: u64, // Most the bidder will pay per ticket; the bid is placed at the current price
+    quantity: u8,
+    tier: u8,
 ) -> Result<()> {
     let event = &mut context.accounts.event;
     let bid = &mut context.accounts.bid;
@@ -43,6 +106,16 @@ pub fn place_bid(
     let clock = Clock::get()?;
     let now = clock.unix_timestamp;
 
+    // Auto-activating events become active with the first bid after the auction starts
+    if event.activate_if_due(now) {
+        msg!("Event auto-activated at auction start");
+    }
+
+    // Sealed-bid auctions take commitments through commit_bid instead
+    if event.is_sealed_bid() {
+        return Err(error!(ErrorCode::WrongAuctionType));
+    }
+
     // Check auction status
     if event.status != EVENT_STATUS_ACTIVE {
         return Err(error!(ErrorCode::AuctionNotActive));
@@ -50,37 +123,131 @@ pub fn place_bid(
     if now < event.auction_start_time {
         return Err(error!(ErrorCode::AuctionNotStarted));
     }
-    if now > event.auction_end_time {
+    if now > event.effective_end_time() {
         return Err(error!(ErrorCode::AuctionEnded));
     }
 
-    // Calculate current auction price
-    let current_price = event.get_current_auction_price(now);
-    if amount != current_price {
-        return Err(error!(ErrorCode::BidNotAtCurrentPrice));
-    }
-
-    // Escrow funds from bidder to event PDA
-    let ix = anchor_lang::solana_program::system_instruction::transfer(
-        &bidder.key(),
-        &event_pda.key(),
-        amount,
-    );
-    anchor_lang::solana_program::program::invoke(
-        &ix,
-        &[
-            bidder.to_account_info(),
-            event_pda.to_account_info(),
-            context.accounts.system_program.to_account_info(),
-        ],
-    ).map_err(|_| error!(ErrorCode::CustomError))?;
+    // The bid is placed (and escrowed) at the tier's current price, if the bidder's maximum covers it
+    let amount = event.bid_price(tier, max_price, now)?;
+
+    if quantity == 0 || quantity > MAX_TICKETS_PER_BID {
+        return Err(error!(ErrorCode::InvalidBidQuantity));
+    }
+    let escrow_amount = amount
+        .checked_mul(quantity as u64)
+        .ok_or(error!(ErrorCode::CustomError))?;
+
+    // Enforce the per-wallet ticket cap
+    match &context.accounts.user {
+        Some(user) if !event.within_wallet_cap(user.tickets_held(&event.key()), quantity as u32) => {
+            return Err(error!(ErrorCode::WalletTicketCapExceeded));
+        }
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        _ => {}
+    }
+
+    // Instant-buy events hand the tickets over straight away, so they have to be available now
+    if event.instant_buy {
+        if context.accounts.ticket.is_none() || context.accounts.inventory_page.is_none() {
+            return Err(error!(ErrorCode::TicketAccountsMissing));
+        }
+        if event.tickets_left(tier) < quantity as u32 {
+            return Err(error!(ErrorCode::TicketsSoldOut));
+        }
+    }
+
+    // Escrow funds from bidder to event PDA (or its token vault)
+    if event.is_token_denominated() {
+        let (Some(payment_mint), Some(bidder_token_account), Some(event_vault), Some(token_program)) = (
+            &context.accounts.payment_mint,
+            &context.accounts.bidder_token_account,
+            &context.accounts.event_vault,
+            &context.accounts.token_program,
+        ) else {
+            return Err(error!(ErrorCode::PaymentAccountsMissing));
+        };
+        transfer_tokens(
+            bidder_token_account,
+            event_vault,
+            &escrow_amount,
+            payment_mint,
+            &bidder.to_account_info(),
+            token_program,
+            None,
+        )?;
+    } else {
+        transfer_lamports(
+            &bidder.to_account_info(),
+            &event_pda.to_account_info(),
+            escrow_amount,
+            &context.accounts.system_program,
+            None,
+        )?;
+    }
+
+    // Bids in the closing seconds extend the auction so it can't be sniped
+    if event.extend_for_bid(now) {
+        msg!("Auction extended to {}", event.extended_end_time);
+    }
 
     // Record the bid
     bid.bidder = bidder.key();
     bid.event = event.key();
     bid.amount = amount;
-    bid.status = BID_STATUS_PENDING;
     bid.bump = context.bumps.bid;
+    bid.quantity = quantity;
+    bid.quantity_awarded = 0;
+    bid.tier = tier;
+    bid.settled = false;
+    bid.open(event, clock.slot)?;
+    event.bids_open = event.bids_open.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+
+    // Instant-buy events award the bid and issue its ticket in the same instruction
+    if let (true, Some(ticket), Some(ticket_bump), Some(inventory_page)) = (
+        event.instant_buy,
+        &mut context.accounts.ticket,
+        context.bumps.ticket,
+        &context.accounts.inventory_page,
+    ) {
+        check_award(event, bid, quantity, now)?;
+        let event_key = event.key();
+        if let Some(user) = &mut context.accounts.user {
+            user.record_tickets(&event_key, quantity as u32)?;
+        }
+
+        // Take the next unawarded assets from the inventory, in order
+        let inventory_index = event.assets_awarded;
+        let asset_ids = {
+            let inventory_page = inventory_page.load()?;
+            let next_inventory_page = match &context.accounts.next_inventory_page {
+                Some(next_inventory_page) => Some(next_inventory_page.load()?),
+                None => None,
+            };
+            take_assets(event, &inventory_page, next_inventory_page.as_deref(), quantity)?
+        };
+
+        // Bubblegum transfers go here once the integration is enabled, as in award_ticket
+        #[cfg(not(feature = "bubblegum"))]
+        for asset_id in &asset_ids {
+            msg!("Bubblegum feature not enabled - simulating cNFT transfer for asset ID: {}", asset_id);
+        }
+
+        record_award(event, bid, quantity);
+
+        ticket.owner = bid.bidder;
+        ticket.original_owner = bid.bidder;
+        ticket.event = event_key;
+        ticket.status = TICKET_STATUS_OWNED;
+        ticket.offchain_ref = String::new(); // To be set by user later
+        ticket.bump = ticket_bump;
+        ticket.cnft_asset_id = asset_ids[0];
+        ticket.quantity = quantity;
+        ticket.tier = bid.tier;
+        ticket.inventory_index = inventory_index;
+        ticket.awarded = true;
+    }
 
     Ok(())
 }
@@ -89,9 +256,19 @@ pub fn place_bid(
 pub struct AwardTicketAccountConstraints<'info> {
     #[account(mut)]
     pub organizer: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump = event.bump,
+        has_one = organizer @ ErrorCode::CustomError,
+    )]
     pub event: Account<'info, Event>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
     pub bid: Account<'info, Bid>,
     #[account(
         init,
@@ -101,6 +278,29 @@ pub struct AwardTicketAccountConstraints<'info> {
         bump
     )]
     pub ticket: Account<'info, Ticket>,
+    /// Bidder's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", bid.bidder.as_ref()], bump = user.bump)]
+    pub user: Option<Account<'info, User>>,
+    /// Inventory page holding the next unawarded asset
+    #[account(
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            TicketInventoryPage::locate(event.assets_awarded).0.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inventory_page: AccountLoader<'info, TicketInventoryPage>,
+    /// Following inventory page (only needed when the award runs past the end of inventory_page)
+    #[account(
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            (TicketInventoryPage::locate(event.assets_awarded).0 + 1).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub next_inventory_page: Option<AccountLoader<'info, TicketInventoryPage>>,
     /// Bubblegum Merkle Tree for cNFTs
     /// CHECK: Verified in Bubblegum program CPI call
     #[account(mut)]
@@ -122,7 +322,7 @@ pub struct AwardTicketAccountConstraints<'info> {
 
 pub fn award_ticket(
     context: Context<AwardTicketAccountConstraints>,
-    cnft_asset_id: Pubkey, // Asset ID to transfer
+    quantity: u8, // Number of the bid's tickets to award
 ) -> Result<()> {
     let event = &mut context.accounts.event;
     let bid = &mut context.accounts.bid;
@@ -133,35 +333,53 @@ pub fn award_ticket(
     if event.organizer != organizer.key() {
         return Err(error!(ErrorCode::CustomError)); // Replace with specific error if desired
     }
-    
-    // Check event and bid status
-    if event.status != EVENT_STATUS_ACTIVE {
-        return Err(error!(ErrorCode::AuctionNotActive));
+    if event.sequential_awards {
+        return Err(error!(ErrorCode::SequentialAwardsRequired));
     }
     
-    // Use the helper method to check if bid can be awarded
-    if !bid.can_award() {
-        return Err(error!(ErrorCode::CustomError)); // Replace with BidNotPending if desired
-    }
-    
-    // Check if tickets are still available
-    if event.tickets_awarded >= event.ticket_supply {
-        return Err(error!(ErrorCode::CustomError)); // Replace with TicketsSoldOut if desired
+    // Check the bid can be awarded and there are tickets left for it
+    let now = Clock::get()?.unix_timestamp;
+    check_award(event, bid, quantity, now)?;
+
+    // Enforce the per-wallet ticket cap and record the bidder's holdings
+    let event_key = event.key();
+    match &mut context.accounts.user {
+        Some(user) => {
+            if !event.within_wallet_cap(user.tickets_held(&event_key), quantity as u32) {
+                return Err(error!(ErrorCode::WalletTicketCapExceeded));
+            }
+            user.record_tickets(&event_key, quantity as u32)?;
+        }
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        None => {}
     }
 
+    // Take the next unawarded assets from the inventory, in order
+    let inventory_index = event.assets_awarded;
+    let asset_ids = {
+        let inventory_page = context.accounts.inventory_page.load()?;
+        let next_inventory_page = match &context.accounts.next_inventory_page {
+            Some(next_inventory_page) => Some(next_inventory_page.load()?),
+            None => None,
+        };
+        take_assets(event, &inventory_page, next_inventory_page.as_deref(), quantity)?
+    };
+
     // We'll use these in both branches
     let _event_pda_seeds: &[&[u8]] = &[b"event", event.organizer.as_ref(), &[event.bump]];
 
     // Bubblegum CPI: Transfer cNFT from event PDA to winner
     #[cfg(feature = "bubblegum")]
-    {
+    for _asset_id in &asset_ids {
         // This code will be enabled when we properly integrate Bubblegum
         // let transfer_ix = bubblegum_instruction::transfer_v2(
         //     context.accounts.bubblegum_program.key(),
         //     context.accounts.merkle_tree.key(),
         //     event.key(), // event PDA as current owner
         //     bid.bidder,  // new owner (winner)
-        //     cnft_asset_id,
+        //     *_asset_id,
         //     event.key(), // event PDA as authority
         //     None, // leaf delegate (optional)
         //     None, // collection (optional)
@@ -184,93 +402,841 @@ pub fn award_ticket(
 
     // When bubblegum feature is not enabled, we just simulate the transfer
     #[cfg(not(feature = "bubblegum"))]
-    msg!("Bubblegum feature not enabled - simulating cNFT transfer for asset ID: {}", cnft_asset_id);
+    for asset_id in &asset_ids {
+        msg!("Bubblegum feature not enabled - simulating cNFT transfer for asset ID: {}", asset_id);
+    }
 
     // Mark bid as awarded
-    bid.status = BID_STATUS_AWARDED;
-    event.tickets_awarded = event.tickets_awarded.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+    record_award(event, bid, quantity);
 
     // Create ticket
     ticket.owner = bid.bidder;
+    ticket.original_owner = bid.bidder;
     ticket.event = event.key();
     ticket.status = TICKET_STATUS_OWNED;
     ticket.offchain_ref = String::new(); // To be set by user later
     ticket.bump = context.bumps.ticket;
-    ticket.cnft_asset_id = cnft_asset_id;
+    ticket.cnft_asset_id = asset_ids[0];
+    ticket.quantity = quantity;
+    ticket.tier = bid.tier;
+    ticket.inventory_index = inventory_index;
+    ticket.awarded = true;
 
     Ok(())
 }
 
 #[derive(Accounts)]
-pub struct RefundBidAccountConstraints<'info> {
+pub struct AwardTicketsBatchAccountConstraints<'info> {
     #[account(mut)]
-    pub bidder: Signer<'info>,
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump = event.bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+    /// Inventory page holding the next unawarded asset
+    #[account(
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            TicketInventoryPage::locate(event.assets_awarded).0.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inventory_page: AccountLoader<'info, TicketInventoryPage>,
+    /// Following inventory page (only needed when the batch runs past the end of inventory_page)
+    #[account(
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            (TicketInventoryPage::locate(event.assets_awarded).0 + 1).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub next_inventory_page: Option<AccountLoader<'info, TicketInventoryPage>>,
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: writable (bid, ticket PDA) pairs, or (bid, ticket PDA, user) triples
+    // when the event caps tickets per wallet
+}
+
+// Handle the award tickets batch instruction by:
+// 1. Running the same checks as award_ticket on each bid, awarding every ticket the bid is for
+// 2. Skipping (rather than failing on) bids that can't be awarded, e.g. non-pending bids or sold-out supply
+// 3. Creating a Ticket PDA for each awarded bid, with the event's counters written back once at the end
+// 4. Returning the skipped bids and why they were skipped as return data
+pub fn award_tickets_batch<'info>(
+    context: Context<'_, '_, 'info, 'info, AwardTicketsBatchAccountConstraints<'info>>,
+) -> Result<Vec<SkippedBid>> {
+    let event = &mut context.accounts.event;
+    let organizer = &context.accounts.organizer;
+    let now = Clock::get()?.unix_timestamp;
+    let event_key = event.key();
+
+    if event.sequential_awards {
+        return Err(error!(ErrorCode::SequentialAwardsRequired));
+    }
+
+    let stride = if event.max_tickets_per_wallet > 0 { 3 } else { 2 };
+    let batch_accounts = context.remaining_accounts;
+    if !batch_accounts.len().is_multiple_of(stride) || batch_accounts.len() / stride > MAX_AWARD_BATCH_BIDS {
+        return Err(error!(ErrorCode::InvalidAwardBatch));
+    }
+
+    let inventory_page = context.accounts.inventory_page.load()?;
+    let next_inventory_page = match &context.accounts.next_inventory_page {
+        Some(next_inventory_page) => Some(next_inventory_page.load()?),
+        None => None,
+    };
+    let ticket_space = Ticket::DISCRIMINATOR.len() + Ticket::INIT_SPACE;
+    let ticket_rent = Rent::get()?.minimum_balance(ticket_space);
+
+    let mut skipped = Vec::new();
+    for award_accounts in batch_accounts.chunks(stride) {
+        let ticket_info = &award_accounts[1];
+        let mut bid = Account::<Bid>::try_from(&award_accounts[0])?;
+        if bid.event != event_key {
+            return Err(error!(ErrorCode::InvalidAwardBatch));
+        }
+        let (ticket_address, ticket_bump) = Pubkey::find_program_address(
+            &[b"ticket", event_key.as_ref(), bid.bidder.as_ref()],
+            &crate::ID,
+        );
+        if ticket_info.key() != ticket_address {
+            return Err(error!(ErrorCode::InvalidAwardBatch));
+        }
+        let mut user = match award_accounts.get(2) {
+            Some(user_info) => {
+                let user = Account::<User>::try_from(user_info)?;
+                let user_address = Pubkey::create_program_address(
+                    &[b"user", bid.bidder.as_ref(), &[user.bump]],
+                    &crate::ID,
+                ).map_err(|_| error!(ErrorCode::InvalidAwardBatch))?;
+                if user_info.key() != user_address {
+                    return Err(error!(ErrorCode::InvalidAwardBatch));
+                }
+                Some(user)
+            }
+            None => None,
+        };
+
+        // Award the whole bid, or skip it with the error award_ticket would have returned
+        let quantity = bid.quantity;
+        let eligibility = check_award(event, &bid, quantity, now).and_then(|()| {
+            if ticket_info.lamports() > 0 {
+                return Err(error!(ErrorCode::TicketAlreadyIssued));
+            }
+            if let Some(user) = &mut user {
+                if !event.within_wallet_cap(user.tickets_held(&event_key), quantity as u32) {
+                    return Err(error!(ErrorCode::WalletTicketCapExceeded));
+                }
+                user.record_tickets(&event_key, quantity as u32)?;
+            }
+            Ok(())
+        });
+        match eligibility {
+            Ok(()) => {}
+            Err(Error::AnchorError(error)) => {
+                skipped.push(SkippedBid { bid: bid.key(), error_code: error.error_code_number });
+                continue;
+            }
+            Err(error) => return Err(error),
+        }
+
+        // Take the next unawarded assets from the inventory, in order
+        let inventory_index = event.assets_awarded;
+        let asset_ids = take_assets(event, &inventory_page, next_inventory_page.as_deref(), quantity)?;
+
+        // Bubblegum transfers go here once the integration is enabled, as in award_ticket
+        #[cfg(not(feature = "bubblegum"))]
+        for asset_id in &asset_ids {
+            msg!("Bubblegum feature not enabled - simulating cNFT transfer for asset ID: {}", asset_id);
+        }
+
+        // Create the ticket PDA, paid for by the organizer
+        create_account(
+            CpiContext::new_with_signer(
+                context.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: organizer.to_account_info(),
+                    to: ticket_info.clone(),
+                },
+                &[&[b"ticket", event_key.as_ref(), bid.bidder.as_ref(), &[ticket_bump]]],
+            ),
+            ticket_rent,
+            ticket_space as u64,
+            &crate::ID,
+        )?;
+        let ticket = Ticket {
+            owner: bid.bidder,
+            event: event_key,
+            status: TICKET_STATUS_OWNED,
+            offchain_ref: String::new(), // To be set by user later
+            bump: ticket_bump,
+            cnft_asset_id: asset_ids[0],
+            quantity,
+            tier: bid.tier,
+            inventory_index,
+            original_owner: bid.bidder,
+            awarded: true,
+        };
+        ticket.try_serialize(&mut &mut ticket_info.try_borrow_mut_data()?[..])?;
+
+        record_award(event, &mut bid, quantity);
+        bid.exit(&crate::ID)?;
+        if let Some(user) = &user {
+            user.exit(&crate::ID)?;
+        }
+    }
+
+    Ok(skipped)
+}
+
+#[derive(Accounts)]
+pub struct AwardNextTicketAccountConstraints<'info> {
+    /// Anyone can run the crank; they pay the rent for the new ticket
     #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub bid: Account<'info, Bid>,
+    #[account(
+        init,
+        payer = payer,
+        space = Ticket::DISCRIMINATOR.len() + Ticket::INIT_SPACE,
+        seeds = [b"ticket", event.key().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub ticket: Account<'info, Ticket>,
+    /// Bidder's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", bid.bidder.as_ref()], bump = user.bump)]
+    pub user: Option<Account<'info, User>>,
+    /// Inventory page holding the next unawarded asset
+    #[account(
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            TicketInventoryPage::locate(event.assets_awarded).0.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inventory_page: AccountLoader<'info, TicketInventoryPage>,
+    /// Following inventory page (only needed when the award runs past the end of inventory_page)
+    #[account(
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            (TicketInventoryPage::locate(event.assets_awarded).0 + 1).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub next_inventory_page: Option<AccountLoader<'info, TicketInventoryPage>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the award next ticket crank by:
+// 1. Checking the event awards in sequence and this bid is the next one
+// 2. Awarding as many of the bid's tickets as are left in its tier, overall and under the wallet cap,
+//    so a bid for more than what's left gets the remainder rather than holding up the queue
+// 3. Running the same checks as award_ticket, then creating the ticket and moving on to the next bid
+pub fn award_next_ticket(
+    context: Context<AwardNextTicketAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let bid = &mut context.accounts.bid;
+    let ticket = &mut context.accounts.ticket;
+
+    if !event.sequential_awards {
+        return Err(error!(ErrorCode::SequentialAwardsNotEnabled));
+    }
+    if bid.sequence != event.next_award_sequence {
+        return Err(error!(ErrorCode::BidOutOfSequence));
+    }
+
+    // Work out how many tickets the bid gets, recording them against the bidder's holdings
+    let event_key = event.key();
+    let quantity = match &mut context.accounts.user {
+        Some(user) => {
+            let quantity = event.sequential_award_quantity(bid.tier, bid.quantity, user.tickets_held(&event_key));
+            if quantity > 0 {
+                user.record_tickets(&event_key, quantity as u32)?;
+            }
+            quantity
+        }
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        None => event.sequential_award_quantity(bid.tier, bid.quantity, 0),
+    };
+    let now = Clock::get()?.unix_timestamp;
+    check_award(event, bid, quantity, now)?;
+
+    // Take the next unawarded assets from the inventory, in order
+    let inventory_index = event.assets_awarded;
+    let asset_ids = {
+        let inventory_page = context.accounts.inventory_page.load()?;
+        let next_inventory_page = match &context.accounts.next_inventory_page {
+            Some(next_inventory_page) => Some(next_inventory_page.load()?),
+            None => None,
+        };
+        take_assets(event, &inventory_page, next_inventory_page.as_deref(), quantity)?
+    };
+
+    // Bubblegum transfers go here once the integration is enabled, as in award_ticket
+    #[cfg(not(feature = "bubblegum"))]
+    for asset_id in &asset_ids {
+        msg!("Bubblegum feature not enabled - simulating cNFT transfer for asset ID: {}", asset_id);
+    }
+
+    record_award(event, bid, quantity);
+    event.next_award_sequence += 1;
+
+    ticket.owner = bid.bidder;
+    ticket.original_owner = bid.bidder;
+    ticket.event = event_key;
+    ticket.status = TICKET_STATUS_OWNED;
+    ticket.offchain_ref = String::new(); // To be set by user later
+    ticket.bump = context.bumps.ticket;
+    ticket.cnft_asset_id = asset_ids[0];
+    ticket.quantity = quantity;
+    ticket.tier = bid.tier;
+    ticket.inventory_index = inventory_index;
+    ticket.awarded = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SkipBidAccountConstraints<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
     pub event: Account<'info, Event>,
+    #[account(
+        seeds = [b"bid", event.key().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub bid: Account<'info, Bid>,
+    /// Bidder's user account (required when the event caps tickets per wallet)
+    #[account(seeds = [b"user", bid.bidder.as_ref()], bump = user.bump)]
+    pub user: Option<Account<'info, User>>,
+}
+
+// Handle the skip bid crank by:
+// 1. Checking the event awards in sequence and this bid is the next one
+// 2. Checking the bid can never be awarded: it is no longer pending, or its tier, the event or
+//    the bidder's wallet cap has no tickets left for it
+// 3. Moving on to the next bid, leaving this one to be refunded as usual
+pub fn skip_bid(
+    context: Context<SkipBidAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let bid = &context.accounts.bid;
+
+    if !event.sequential_awards {
+        return Err(error!(ErrorCode::SequentialAwardsNotEnabled));
+    }
+    if bid.sequence != event.next_award_sequence {
+        return Err(error!(ErrorCode::BidOutOfSequence));
+    }
+
+    let tickets_held = match &context.accounts.user {
+        Some(user) => user.tickets_held(&event.key()),
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        None => 0,
+    };
+    if bid.can_award() && event.sequential_award_quantity(bid.tier, bid.quantity, tickets_held) > 0 {
+        return Err(error!(ErrorCode::BidStillAwardable));
+    }
+
+    event.next_award_sequence += 1;
+
+    Ok(())
+}
+
+// Checks shared by place_bid (for instant-buy events), award_ticket, award_tickets_batch and
+// award_next_ticket: the auction is awarding, the bid is pending and eligible, and there are
+// enough tickets left overall and in the bid's tier
+fn check_award(event: &Event, bid: &Bid, quantity: u8, now: i64) -> Result<()> {
+    // Check event and bid status
+    if event.status != EVENT_STATUS_ACTIVE {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+    
+    // Use the helper method to check if bid can be awarded
+    if !bid.can_award() {
+        return Err(error!(ErrorCode::CustomError)); // Replace with BidNotPending if desired
+    }
+    
+    // Once a lottery is committed, bids in its pool are only awarded if they were drawn
+    if event.has_lottery() && event.is_lottery_pool_bid(bid.tier, bid.amount) && !(event.lottery_drawn && bid.lottery_winner) {
+        return Err(error!(ErrorCode::NotLotteryWinner));
+    }
+    
+    // Sealed bids are only awarded once every bid has had its chance to be revealed, and then
+    // only if rank_sealed_bids placed them among the winners
+    if event.is_sealed_bid() && event.auction_phase(now) != AUCTION_PHASE_CLOSED {
+        return Err(error!(ErrorCode::SealedBidPhaseMismatch));
+    }
+    if event.is_sealed_bid() && !(event.sealed_bids_ranked && bid.sealed_winner) {
+        return Err(error!(ErrorCode::NotSealedBidWinner));
+    }
+    
+    // Award some or all of the tickets the bid is for
+    if quantity == 0 {
+        return Err(error!(ErrorCode::InvalidBidQuantity));
+    }
+    if quantity > bid.quantity {
+        return Err(error!(ErrorCode::AwardQuantityExceedsBid));
+    }
+    
+    // Check if tickets are still available, both overall and in the bid's tier
+    let tickets_awarded = event
+        .tickets_awarded
+        .checked_add(quantity as u32)
+        .ok_or(error!(ErrorCode::CustomError))?;
+    if tickets_awarded > event.ticket_supply {
+        return Err(error!(ErrorCode::CustomError)); // Replace with TicketsSoldOut if desired
+    }
+    let ticket_tier = event
+        .tiers
+        .get(bid.tier as usize)
+        .ok_or(error!(ErrorCode::InvalidTicketTier))?;
+    let tier_awarded = ticket_tier
+        .awarded
+        .checked_add(quantity as u32)
+        .ok_or(error!(ErrorCode::CustomError))?;
+    if tier_awarded > ticket_tier.supply {
+        return Err(error!(ErrorCode::CustomError)); // Replace with TicketsSoldOut if desired
+    }
+    
+    // The tickets also need registered cNFT assets to hand out
+    let assets_awarded = event
+        .assets_awarded
+        .checked_add(quantity as u32)
+        .ok_or(error!(ErrorCode::CustomError))?;
+    if assets_awarded > event.assets_registered {
+        return Err(error!(ErrorCode::TicketAssetsNotRegistered));
+    }
+    
+    Ok(())
+}
+
+// Look up the asset IDs for the next `quantity` unawarded tickets, which live in the given
+// inventory page or (once an award runs past its end) the page after it
+fn take_assets(
+    event: &Event,
+    inventory_page: &TicketInventoryPage,
+    next_inventory_page: Option<&TicketInventoryPage>,
+    quantity: u8,
+) -> Result<Vec<Pubkey>> {
+    let first_index = event.assets_awarded;
+    let mut asset_ids = Vec::with_capacity(quantity as usize);
+    for ticket_index in first_index..first_index + quantity as u32 {
+        let (page_index, slot) = TicketInventoryPage::locate(ticket_index);
+        let page = if page_index == inventory_page.page_index {
+            inventory_page
+        } else {
+            next_inventory_page
+                .filter(|next_page| next_page.page_index == page_index)
+                .ok_or(error!(ErrorCode::InvalidInventoryPage))?
+        };
+        let asset_id = page
+            .asset_at(slot)
+            .ok_or(error!(ErrorCode::TicketAssetsNotRegistered))?;
+        asset_ids.push(asset_id);
+    }
+    Ok(asset_ids)
+}
+
+// Mark a bid as awarded and count its tickets against the event and its tier, taking it out of
+// the lottery pool or the tickets held back from the draw
+fn record_award(event: &mut Event, bid: &mut Bid, quantity: u8) {
+    event.award_processing_started = true;
+    bid.leave_auction(event);
+    bid.status = BID_STATUS_AWARDED;
+    bid.quantity_awarded = quantity;
+    event.tickets_awarded += quantity as u32;
+    event.assets_awarded += quantity as u32;
+    event.tiers[bid.tier as usize].awarded += quantity as u32;
+    event.tiers[bid.tier as usize].record_award_price(bid.amount);
+    if event.lowest_winning_bid == 0 || bid.amount < event.lowest_winning_bid {
+        event.lowest_winning_bid = bid.amount;
+    }
+}
+
+#[derive(Accounts)]
+pub struct RefundBidAccountConstraints<'info> {
     #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        has_one = bidder @ ErrorCode::AccountRelationshipMismatch,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
     pub bid: Account<'info, Bid>,
     /// Event PDA (escrow authority)
     #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
     pub event_pda: SystemAccount<'info>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn refund_bid(
     context: Context<RefundBidAccountConstraints>,
+) -> Result<()> {
+    let event = &context.accounts.event;
+    let bid = &mut context.accounts.bid;
+    let bidder = &context.accounts.bidder;
+    let event_pda = &context.accounts.event_pda;
+
+    // Work out the refund (full for losing bids, the price difference for winners) and mark the bid settled
+    let refund_amount = bid.settle(event)?;
+
+    if refund_amount > 0 {
+        let event_key = event.key();
+        let bump = &[context.bumps.event_pda];
+        let event_pda_seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), bump];
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(bidder_token_account), Some(event_vault), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.bidder_token_account,
+                &context.accounts.event_vault,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                event_vault,
+                bidder_token_account,
+                &refund_amount,
+                payment_mint,
+                &event_pda.to_account_info(),
+                token_program,
+                Some(event_pda_seeds),
+            )?;
+        } else {
+            transfer_lamports(
+                &event_pda.to_account_info(),
+                &bidder.to_account_info(),
+                refund_amount,
+                &context.accounts.system_program,
+                Some(event_pda_seeds),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBidAccountConstraints<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+        has_one = bidder @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub bid: Account<'info, Bid>,
+    /// The organizer, who receives the withdrawal fee
+    #[account(mut, address = event.organizer @ ErrorCode::CustomError)]
+    pub organizer: SystemAccount<'info>,
+    /// Event PDA (escrow authority)
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = organizer,
+        associated_token::token_program = token_program
+    )]
+    pub organizer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the withdraw bid instruction by:
+// 1. Checking the bid is pending (or a limit bid) in an active Dutch auction whose award processing hasn't
+//    started; sealed bids are only refunded once the auction is over
+// 2. Working out the withdrawal fee under the event's policy: free before the cutoff, the fee after it
+// 3. Refunding the rest of the bid to the bidder and paying the fee to the organizer
+// 4. Closing the bid and returning its rent to the bidder, unless it still holds a place in a
+//    sequential-award queue (then skip_bid moves past it and close_bid reclaims the rent later)
+pub fn withdraw_bid(
+    context: Context<WithdrawBidAccountConstraints>,
 ) -> Result<()> {
     let event = &mut context.accounts.event;
     let bid = &mut context.accounts.bid;
     let bidder = &context.accounts.bidder;
+    let organizer = &context.accounts.organizer;
     let event_pda = &context.accounts.event_pda;
 
-    // Use the helper method to check if bid can be refunded
-    if !bid.can_refund() {
-        return Err(error!(ErrorCode::CustomError)); // Already refunded
-    }
-
-    let refund_amount;
-    if bid.status == BID_STATUS_PENDING {
-        // Case 1: Bid did not win, full refund
-        refund_amount = bid.amount;
-        bid.status = BID_STATUS_REFUNDED;
-    } else if bid.status == BID_STATUS_AWARDED {
-        // Case 2: Bid won, partial refund if closing price < bid amount
-        // We need the auction to be finalized to know the closing price
-        if event.status != EVENT_STATUS_FINALIZED || event.auction_close_price == 0 {
-            return Err(error!(ErrorCode::CustomError)); // Auction not finalized, can't refund
+    let now = Clock::get()?.unix_timestamp;
+    let (refund_amount, fee) = bid.withdraw(event, now)?;
+
+    let event_key = event.key();
+    let bump = &[context.bumps.event_pda];
+    let event_pda_seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), bump];
+    let payouts = [
+        (refund_amount, bidder.to_account_info(), &context.accounts.bidder_token_account),
+        (fee, organizer.to_account_info(), &context.accounts.organizer_token_account),
+    ];
+    for (amount, recipient, recipient_token_account) in payouts {
+        if amount == 0 {
+            continue;
         }
-        
-        let close_price = event.auction_close_price;
-        if bid.amount > close_price {
-            refund_amount = bid.amount - close_price;
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(recipient_token_account), Some(event_vault), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                recipient_token_account,
+                &context.accounts.event_vault,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                event_vault,
+                recipient_token_account,
+                &amount,
+                payment_mint,
+                &event_pda.to_account_info(),
+                token_program,
+                Some(event_pda_seeds),
+            )?;
         } else {
-            // No refund needed
-            return Ok(());
+            transfer_lamports(
+                &event_pda.to_account_info(),
+                &recipient,
+                amount,
+                &context.accounts.system_program,
+                Some(event_pda_seeds),
+            )?;
         }
-        // Do not mark as refunded, as the ticket is already awarded
-    } else {
-        return Err(error!(ErrorCode::CustomError)); // Invalid bid status
     }
 
+    if !bid.holds_queue_position(event) {
+        event.bids_open = event.bids_open.checked_sub(1).ok_or(error!(ErrorCode::CustomError))?;
+        bid.close(bidder.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleBidAccountConstraints<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub bid: Account<'info, Bid>,
+    /// The bidder being paid out (anyone can run the crank, but funds only go to the bidder)
+    #[account(mut, address = bid.bidder @ ErrorCode::CustomError)]
+    pub bidder: SystemAccount<'info>,
+    /// Event PDA (escrow authority)
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the settle bid crank by:
+// 1. Checking the event uses uniform-price settlement and its clearing price is known
+// 2. Working out what the bid is owed and marking it settled (so it can't be paid twice)
+// 3. Paying the bidder from the escrow
+pub fn settle_bid(
+    context: Context<SettleBidAccountConstraints>,
+) -> Result<()> {
+    let event = &context.accounts.event;
+    let bid = &mut context.accounts.bid;
+    let bidder = &context.accounts.bidder;
+    let event_pda = &context.accounts.event_pda;
+
+    if !event.uniform_price {
+        return Err(error!(ErrorCode::UniformPriceNotEnabled));
+    }
+
+    // Pending bids may still win until the auction is finalized (or fails)
+    if event.status != EVENT_STATUS_FINALIZED && event.status != EVENT_STATUS_FAILED {
+        return Err(error!(ErrorCode::AuctionNotFinalized));
+    }
+
+    let refund_amount = bid.settle(event)?;
+
     if refund_amount > 0 {
         let event_key = event.key();
         let bump = &[context.bumps.event_pda];
         let event_pda_seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), bump];
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &event_pda.key(),
-            &bidder.key(),
-            refund_amount,
-        );
-        anchor_lang::solana_program::program::invoke_signed(
-            &ix,
-            &[
-                event_pda.to_account_info(),
-                bidder.to_account_info(),
-                context.accounts.system_program.to_account_info(),
-            ],
-            &[event_pda_seeds],
-        ).map_err(|_| error!(ErrorCode::CustomError))?;
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(bidder_token_account), Some(event_vault), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.bidder_token_account,
+                &context.accounts.event_vault,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                event_vault,
+                bidder_token_account,
+                &refund_amount,
+                payment_mint,
+                &event_pda.to_account_info(),
+                token_program,
+                Some(event_pda_seeds),
+            )?;
+        } else {
+            transfer_lamports(
+                &event_pda.to_account_info(),
+                &bidder.to_account_info(),
+                refund_amount,
+                &context.accounts.system_program,
+                Some(event_pda_seeds),
+            )?;
+        }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct CloseBidAccountConstraints<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [b"bid", event.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+        has_one = bidder @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+// Handle the close bid instruction by:
+// 1. Checking everything owed to the bidder has been paid out
+// 2. Removing the bid from the event's open bid count
+// 3. Closing the bid account, returning its rent to the bidder
+pub fn close_bid(
+    context: Context<CloseBidAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let bid = &context.accounts.bid;
+
+    if !bid.can_close() || bid.holds_queue_position(event) {
+        return Err(error!(ErrorCode::BidNotSettled));
+    }
+
+    event.bids_open = event.bids_open.checked_sub(1).ok_or(error!(ErrorCode::CustomError))?;
+
+    Ok(())
+}
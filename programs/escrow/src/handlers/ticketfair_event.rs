@@ -1,7 +1,12 @@
 //! Ticketfair event instruction handlers
 
 use anchor_lang::prelude::*;
-use crate::state::Event;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use super::shared::{close_token_account, transfer_lamports, transfer_tokens};
+use crate::state::{Event, Payee, TicketTier};
 use crate::constants::*;
 use crate::error::ErrorCode;
 
@@ -23,6 +28,22 @@ pub struct CreateEventAccountConstraints<'info> {
         bump
     )]
     pub event: Account<'info, Event>,
+    /// The PDA that will hold escrowed funds for the event
+    /// Seeds: [b"escrow", event.key().as_ref()]
+    #[account(seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    /// SPL token mint bids are paid in (omit for SOL-denominated events)
+    #[account(mint::token_program = token_program)]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    /// Token vault owned by the escrow PDA (required when payment_mint is set)
+    #[account(
+        init,
+        payer = organizer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
     /// Bubblegum Merkle Tree for cNFTs (must be created before event)
     /// CHECK: Verified in Bubblegum program CPI call
     #[account(mut)]
@@ -39,9 +60,12 @@ pub struct CreateEventAccountConstraints<'info> {
     /// Noop program (required by Bubblegum)
     /// CHECK: Program ID verified in CPI
     pub noop_program: UncheckedAccount<'info>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_event(
     context: Context<CreateEventAccountConstraints>,
     metadata_url: String,
@@ -50,37 +74,53 @@ pub fn create_event(
     end_price: u64,
     auction_start_time: i64,
     auction_end_time: i64,
+    min_tickets_sold: u32,
+    max_tickets_per_wallet: u32,
+    auto_activate: bool,
 ) -> Result<()> {
     let event = &mut context.accounts.event;
     
-    // Validate parameters
-    if ticket_supply > MAX_TICKETS_PER_EVENT {
-        return Err(error!(ErrorCode::CustomError)); // Replace with specific error
-    }
-    
-    if start_price < MIN_TICKET_PRICE || end_price < MIN_TICKET_PRICE {
-        return Err(error!(ErrorCode::CustomError)); // Replace with specific error
-    }
-    
-    let duration = auction_end_time - auction_start_time;
-    if duration < MIN_AUCTION_DURATION || duration > MAX_AUCTION_DURATION {
-        return Err(error!(ErrorCode::CustomError)); // Replace with specific error
-    }
+    let payment_mint = match &context.accounts.payment_mint {
+        Some(mint) => {
+            if context.accounts.event_vault.is_none() {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            }
+            mint.key()
+        }
+        None => Pubkey::default(),
+    };
     
     event.organizer = context.accounts.organizer.key();
     event.metadata_url = metadata_url.clone();
     event.ticket_supply = ticket_supply;
     event.tickets_awarded = 0;
+    event.min_tickets_sold = min_tickets_sold;
+    event.max_tickets_per_wallet = max_tickets_per_wallet;
+    event.auto_activate = auto_activate;
     event.start_price = start_price;
     event.end_price = end_price;
     event.auction_start_time = auction_start_time;
     event.auction_end_time = auction_end_time;
+    event.extended_end_time = auction_end_time;
     event.auction_close_price = 0;
+    event.payment_mint = payment_mint;
+    event.proceeds_withdrawn = false;
+    event.tiers = vec![TicketTier {
+        supply: ticket_supply,
+        awarded: 0,
+        start_price,
+        end_price,
+        close_price: 0,
+        waitlist_head: 0,
+        waitlist_tail: 0,
+        lowest_awarded_bid: 0,
+    }];
     event.status = EVENT_STATUS_CREATED;
     event.bump = context.bumps.event;
     event.merkle_tree = context.accounts.merkle_tree.key();
-    let ticket_supply_to_reserve = ticket_supply as usize;
-    event.cnft_asset_ids = Vec::with_capacity(ticket_supply_to_reserve);
+    
+    // Validate parameters
+    event.validate_params()?;
 
     // Bubblegum CPI: Mint cNFTs for ticket supply
     #[cfg(feature = "bubblegum")]
@@ -123,21 +163,16 @@ pub fn create_event(
         //         &[event_pda_seeds],
         //     ).map_err(|_| error!(crate::error::ErrorCode::CustomError))?;
         //
-        //     // TODO: Parse asset ID from transaction logs off-chain and update event.cnft_asset_ids
-        //     // For now, push a placeholder
-        //     event.cnft_asset_ids.push(Pubkey::default());
+        //     // Asset IDs are parsed from transaction logs off-chain and written into the
+        //     // event's TicketInventoryPage accounts with register_ticket_assets
         // }
     }
     
     // When bubblegum feature is not enabled, we just simulate the minting
     #[cfg(not(feature = "bubblegum"))]
     {
+        // Inventory pages hold placeholder asset IDs (Pubkey::default()) until assets are registered
         msg!("Bubblegum feature not enabled - simulating cNFT minting for {} tickets", ticket_supply);
-        // Create placeholder asset IDs for testing
-        event.cnft_asset_ids = Vec::new();
-        for _ in 0..ticket_supply {
-            event.cnft_asset_ids.push(Pubkey::default());
-        }
     }
 
     Ok(())
@@ -172,6 +207,33 @@ pub fn activate_event(
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct ActivateIfDueAccountConstraints<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Permissionless crank so an auto-activating auction doesn't depend on the organizer being around
+pub fn activate_if_due(
+    context: Context<ActivateIfDueAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    // Get current time
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    
+    if !event.activate_if_due(now) {
+        return Err(error!(ErrorCode::EventNotDueForActivation));
+    }
+    
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct FinalizeEventAccountConstraints<'info> {
     #[account(mut)]
@@ -188,6 +250,7 @@ pub struct FinalizeEventAccountConstraints<'info> {
 pub fn finalize_auction(
     context: Context<FinalizeEventAccountConstraints>,
     close_price: u64,
+    tier_close_prices: Vec<u64>, // Close prices for tiers 1.., in tier order
 ) -> Result<()> {
     let event = &mut context.accounts.event;
     
@@ -195,21 +258,691 @@ pub fn finalize_auction(
     let clock = Clock::get()?;
     let now = clock.unix_timestamp;
     
-    // Check if the auction can be finalized
-    if !event.can_finalize(now) {
-        return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+    // Fail the event if it fell short of its minimum sales, otherwise set the close prices
+    event.finalize(close_price, tier_close_prices, now)
+}
+
+#[derive(Accounts)]
+pub struct AddTicketTierAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Handle the add ticket tier instruction by:
+// 1. Checking the event hasn't been activated and has room for another tier
+// 2. Validating the tier's supply and prices the same way create_event does
+// 3. Adding the tier and its supply to the event
+pub fn add_ticket_tier(
+    context: Context<AddTicketTierAccountConstraints>,
+    supply: u32,
+    start_price: u64,
+    end_price: u64,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    // Tiers can only be added before the event is activated
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+    
+    if event.tiers.len() >= MAX_TICKET_TIERS {
+        return Err(error!(ErrorCode::TooManyTicketTiers));
     }
     
-    // Validate close price is between start and end prices
-    if close_price > event.start_price || close_price < event.end_price {
+    if supply == 0 {
         return Err(error!(ErrorCode::CustomError)); // Replace with specific error
     }
+    let ticket_supply = event
+        .ticket_supply
+        .checked_add(supply)
+        .ok_or(error!(ErrorCode::CustomError))?;
+    
+    event.tiers.push(TicketTier {
+        supply,
+        awarded: 0,
+        start_price,
+        end_price,
+        close_price: 0,
+        waitlist_head: 0,
+        waitlist_tail: 0,
+        lowest_awarded_bid: 0,
+    });
+    event.ticket_supply = ticket_supply;
+    event.validate_params()?;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAuctionExtensionAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Configure anti-sniping: a bid within extension_window seconds of the end pushes the end back
+// by extension_duration seconds, up to max_extension seconds past the original end time
+pub fn set_auction_extension(
+    context: Context<SetAuctionExtensionAccountConstraints>,
+    extension_window: i64,
+    extension_duration: i64,
+    max_extension: i64,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    // Extensions can only be configured before the event is activated
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+    
+    if extension_window < 0 || extension_duration < 0 || !(0..=MAX_AUCTION_DURATION).contains(&max_extension) {
+        return Err(error!(ErrorCode::InvalidExtensionConfig));
+    }
+    
+    event.extension_window = extension_window;
+    event.extension_duration = extension_duration;
+    event.max_extension = max_extension;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetUniformPriceAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+pub fn set_uniform_price(
+    context: Context<SetUniformPriceAccountConstraints>,
+    enabled: bool,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    // The settlement mode can only be chosen before the event is activated
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+    
+    event.uniform_price = enabled;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSequentialAwardsAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Handle the set sequential awards instruction by:
+// 1. Checking the event hasn't been activated yet
+// 2. Choosing whether tickets are awarded first-come in bid order by anyone calling award_next_ticket,
+//    instead of by the organizer picking bids with award_ticket
+pub fn set_sequential_awards(
+    context: Context<SetSequentialAwardsAccountConstraints>,
+    enabled: bool,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    // The award mode can only be chosen before the event is activated
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+    
+    event.sequential_awards = enabled;
+    // Sealed bids are ranked by price, not arrival, so validation rejects awarding them in sequence
+    event.validate_params()?;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetInstantBuyAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Handle the set instant buy instruction by:
+// 1. Checking the event hasn't been activated yet
+// 2. Choosing whether place_bid awards the bid and issues its ticket in the same instruction,
+//    rejecting bids once the tier sells out, instead of leaving bids for the organizer to award
+pub fn set_instant_buy(
+    context: Context<SetInstantBuyAccountConstraints>,
+    enabled: bool,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    // The award mode can only be chosen before the event is activated
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+    
+    event.instant_buy = enabled;
+    event.validate_params()?;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawalPolicyAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Handle the set withdrawal policy instruction by:
+// 1. Checking the event hasn't been activated yet, so bidders know the policy before they bid
+// 2. Setting the time after which withdrawn bids pay a fee to the organizer, and the fee
+//    (bids can never be withdrawn once award processing starts)
+pub fn set_withdrawal_policy(
+    context: Context<SetWithdrawalPolicyAccountConstraints>,
+    cutoff: i64,
+    fee_bps: u16,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+    if cutoff < 0 || fee_bps > TOTAL_BASIS_POINTS {
+        return Err(error!(ErrorCode::InvalidWithdrawalPolicy));
+    }
+    
+    event.withdrawal_cutoff = cutoff;
+    event.withdrawal_fee_bps = fee_bps;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTransferPolicyAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Handle the set transfer policy instruction by:
+// 1. Checking the event hasn't been activated yet, so bidders know the policy before they bid
+// 2. Setting whether tickets can be transferred, the resale price cap for capped transfers
+//    (a share of each ticket tier's close price) and the organizer's royalty on every resale,
+//    and checking they're in range
+pub fn set_transfer_policy(
+    context: Context<SetTransferPolicyAccountConstraints>,
+    policy: u8,
+    resale_cap_bps: u16,
+    royalty_bps: u16,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+    
+    event.transfer_policy = policy;
+    event.resale_cap_bps = resale_cap_bps;
+    event.royalty_bps = royalty_bps;
+    event.validate_transfer_policy()?;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateEventAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+        realloc = 8 + Event::INIT_SPACE, // Grows events created before fields were added
+        realloc::payer = organizer,
+        realloc::zero = false,
+    )]
+    pub event: Account<'info, Event>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the update event instruction by:
+// 1. Checking the event hasn't been activated
+// 2. Replacing the metadata URL, base tier supply and prices, and auction times
+// 3. Re-running the same validation as create_event
+pub fn update_event(
+    context: Context<UpdateEventAccountConstraints>,
+    metadata_url: String,
+    ticket_supply: u32,
+    start_price: u64,
+    end_price: u64,
+    auction_start_time: i64,
+    auction_end_time: i64,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    // Events can only be edited before they are activated
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+    
+    let total_supply = event.supply_with_base_tier(ticket_supply);
+    event.metadata_url = metadata_url;
+    event.ticket_supply = total_supply;
+    event.start_price = start_price;
+    event.end_price = end_price;
+    event.auction_start_time = auction_start_time;
+    event.auction_end_time = auction_end_time;
+    event.extended_end_time = auction_end_time;
+    if let Some(base_tier) = event.tiers.first_mut() {
+        base_tier.supply = ticket_supply;
+        base_tier.start_price = start_price;
+        base_tier.end_price = end_price;
+    }
+    
+    // Validate parameters
+    event.validate_params()?;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProceedsAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+    /// Event PDA (escrow authority)
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = organizer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = organizer,
+        associated_token::token_program = token_program
+    )]
+    pub organizer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the withdraw proceeds instruction by:
+// 1. Checking the auction finalized successfully and proceeds weren't already withdrawn
+// 2. Moving tickets_awarded * auction_close_price from the escrow to the organizer
+pub fn withdraw_proceeds(
+    context: Context<WithdrawProceedsAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let organizer = &context.accounts.organizer;
+    let event_pda = &context.accounts.event_pda;
+
+    // Failed events never pay the organizer, so only finalized events have proceeds
+    if event.status != EVENT_STATUS_FINALIZED {
+        return Err(error!(ErrorCode::AuctionNotFinalized));
+    }
+    if event.proceeds_withdrawn {
+        return Err(error!(ErrorCode::ProceedsAlreadyWithdrawn));
+    }
+    if !event.payees.is_empty() {
+        return Err(error!(ErrorCode::ProceedsSplitBetweenPayees));
+    }
+
+    let proceeds = event.proceeds().ok_or(error!(ErrorCode::CustomError))?;
+    event.proceeds_withdrawn = true;
+
+    if proceeds > 0 {
+        let event_key = event.key();
+        let bump = &[context.bumps.event_pda];
+        let event_pda_seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), bump];
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(organizer_token_account), Some(event_vault), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.organizer_token_account,
+                &context.accounts.event_vault,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                event_vault,
+                organizer_token_account,
+                &proceeds,
+                payment_mint,
+                &event_pda.to_account_info(),
+                token_program,
+                Some(event_pda_seeds),
+            )?;
+        } else {
+            transfer_lamports(
+                &event_pda.to_account_info(),
+                &organizer.to_account_info(),
+                proceeds,
+                &context.accounts.system_program,
+                Some(event_pda_seeds),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPayeesAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Handle the set payees instruction by:
+// 1. Checking the event hasn't been activated yet (the split is fixed from then on)
+// 2. Validating the shares add up to 100% and the dust payee is in the table
+// 3. Replacing the event's payee table
+pub fn set_payees(
+    context: Context<SetPayeesAccountConstraints>,
+    payees: Vec<Payee>,
+    dust_payee: u8,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    
+    // Payees can only be changed before the event is activated
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
     
-    // Set the auction close price
-    event.auction_close_price = close_price;
+    Event::validate_payees(&payees, dust_payee)?;
     
-    // Update event status to finalized
-    event.status = EVENT_STATUS_FINALIZED;
+    event.payees = payees;
+    event.dust_payee = dust_payee;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct DistributeProceedsAccountConstraints<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    /// Event PDA (escrow authority)
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: one writable account per payee, in payee table order
+    // (the payee's wallet for SOL events, or their payment mint token account for token events)
+}
+
+// Handle the distribute proceeds instruction by:
+// 1. Checking the auction finalized successfully and proceeds weren't already paid out
+// 2. Splitting the proceeds between the payees by share, with rounding dust to the dust payee
+// 3. Paying every payee from the escrow in this one instruction
+pub fn distribute_proceeds<'info>(
+    context: Context<'_, '_, 'info, 'info, DistributeProceedsAccountConstraints<'info>>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let event_pda = &context.accounts.event_pda;
+
+    // Failed events never pay out, so only finalized events have proceeds
+    if event.status != EVENT_STATUS_FINALIZED {
+        return Err(error!(ErrorCode::AuctionNotFinalized));
+    }
+    if event.proceeds_withdrawn {
+        return Err(error!(ErrorCode::ProceedsAlreadyWithdrawn));
+    }
+    if event.payees.is_empty() {
+        return Err(error!(ErrorCode::InvalidPayeeShares));
+    }
+    if context.remaining_accounts.len() != event.payees.len() {
+        return Err(error!(ErrorCode::PayeeAccountMismatch));
+    }
+
+    let proceeds = event.proceeds().ok_or(error!(ErrorCode::CustomError))?;
+    let amounts = event.payee_amounts(proceeds);
+    event.proceeds_withdrawn = true;
+
+    let event_key = event.key();
+    let bump = &[context.bumps.event_pda];
+    let event_pda_seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), bump];
+    for ((payee, amount), payee_account) in event
+        .payees
+        .iter()
+        .zip(amounts)
+        .zip(context.remaining_accounts.iter())
+    {
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(event_vault), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.event_vault,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            let payee_token_account = InterfaceAccount::<TokenAccount>::try_from(payee_account)?;
+            if payee_token_account.owner != payee.recipient || payee_token_account.mint != payment_mint.key() {
+                return Err(error!(ErrorCode::PayeeAccountMismatch));
+            }
+            if amount > 0 {
+                transfer_tokens(
+                    event_vault,
+                    &payee_token_account,
+                    &amount,
+                    payment_mint,
+                    &event_pda.to_account_info(),
+                    token_program,
+                    Some(event_pda_seeds),
+                )?;
+            }
+        } else {
+            if payee_account.key() != payee.recipient {
+                return Err(error!(ErrorCode::PayeeAccountMismatch));
+            }
+            if amount > 0 {
+                transfer_lamports(
+                    &event_pda.to_account_info(),
+                    payee_account,
+                    amount,
+                    &context.accounts.system_program,
+                    Some(event_pda_seeds),
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseEventAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        close = organizer,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump = event.bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+    /// Event PDA (escrow authority)
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = organizer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = organizer,
+        associated_token::token_program = token_program
+    )]
+    pub organizer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the close event instruction by:
+// 1. Checking every bid account is closed and the proceeds have been paid out
+// 2. Checking no tickets are still listed for resale, as their listings need the event account;
+//    issued tickets are kept as a record of the event once it's closed
+// 3. Sweeping anything left in the escrow (and closing the token vault) to the organizer
+// 4. Closing the event account, returning its rent to the organizer
+pub fn close_event(
+    context: Context<CloseEventAccountConstraints>,
+) -> Result<()> {
+    let event = &context.accounts.event;
+    let organizer = &context.accounts.organizer;
+    let event_pda = &context.accounts.event_pda;
+
+    if !event.is_fully_settled() {
+        return Err(error!(ErrorCode::EventNotSettled));
+    }
+    if event.has_open_listings() {
+        return Err(error!(ErrorCode::TicketsOutstanding));
+    }
+
+    let event_key = event.key();
+    let bump = &[context.bumps.event_pda];
+    let event_pda_seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), bump];
+
+    if event.is_token_denominated() {
+        let (Some(payment_mint), Some(organizer_token_account), Some(event_vault), Some(token_program)) = (
+            &context.accounts.payment_mint,
+            &context.accounts.organizer_token_account,
+            &context.accounts.event_vault,
+            &context.accounts.token_program,
+        ) else {
+            return Err(error!(ErrorCode::PaymentAccountsMissing));
+        };
+        if event_vault.amount > 0 {
+            transfer_tokens(
+                event_vault,
+                organizer_token_account,
+                &event_vault.amount,
+                payment_mint,
+                &event_pda.to_account_info(),
+                token_program,
+                Some(event_pda_seeds),
+            )?;
+        }
+        close_token_account(
+            event_vault,
+            &organizer.to_account_info(),
+            &event_pda.to_account_info(),
+            token_program,
+            Some(event_pda_seeds),
+        )?;
+    }
+
+    // Nothing is owed to anyone, so any lamports left in the escrow belong to the organizer
+    let leftover = event_pda.lamports();
+    if leftover > 0 {
+        transfer_lamports(
+            &event_pda.to_account_info(),
+            &organizer.to_account_info(),
+            leftover,
+            &context.accounts.system_program,
+            Some(event_pda_seeds),
+        )?;
+    }
+
+    Ok(())
+}
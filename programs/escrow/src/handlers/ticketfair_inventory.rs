@@ -0,0 +1,109 @@
+//! Ticketfair ticket inventory instruction handlers
+
+use anchor_lang::prelude::*;
+use crate::state::{Event, TicketInventoryPage};
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(page_index: u32)]
+pub struct CreateTicketInventoryPageAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        init,
+        payer = organizer,
+        space = TicketInventoryPage::DISCRIMINATOR.len() + TicketInventoryPage::INIT_SPACE,
+        seeds = [b"inventory", event.key().as_ref(), page_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub inventory_page: AccountLoader<'info, TicketInventoryPage>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the create ticket inventory page instruction by:
+// 1. Checking the page is needed for the event's ticket supply
+// 2. Initializing an empty page of asset IDs for the event
+pub fn create_ticket_inventory_page(
+    context: Context<CreateTicketInventoryPageAccountConstraints>,
+    page_index: u32,
+) -> Result<()> {
+    let event = &context.accounts.event;
+
+    if page_index >= TicketInventoryPage::page_count(event.ticket_supply) {
+        return Err(error!(ErrorCode::InvalidInventoryPage));
+    }
+
+    let mut inventory_page = context.accounts.inventory_page.load_init()?;
+    inventory_page.event = event.key();
+    inventory_page.page_index = page_index;
+    inventory_page.asset_count = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(start_index: u32)]
+pub struct RegisterTicketAssetsAccountConstraints<'info> {
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump = event.bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+    /// Inventory page holding the ticket at start_index
+    #[account(
+        mut,
+        seeds = [
+            b"inventory",
+            event.key().as_ref(),
+            TicketInventoryPage::locate(start_index).0.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inventory_page: AccountLoader<'info, TicketInventoryPage>,
+}
+
+// Handle the register ticket assets instruction by:
+// 1. Checking the chunk continues where the last one left off and fits in the ticket supply
+// 2. Writing the asset IDs into the inventory page that holds them
+// 3. Counting them as registered on the event
+pub fn register_ticket_assets(
+    context: Context<RegisterTicketAssetsAccountConstraints>,
+    start_index: u32,
+    asset_ids: Vec<Pubkey>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+
+    if asset_ids.is_empty() || asset_ids.len() > MAX_ASSETS_PER_REGISTRATION {
+        return Err(error!(ErrorCode::InvalidAssetRegistration));
+    }
+
+    // Chunks are registered in order so a retried transaction can't overwrite or skip assets
+    if start_index != event.assets_registered {
+        return Err(error!(ErrorCode::InvalidAssetRegistration));
+    }
+
+    let assets_registered = start_index
+        .checked_add(asset_ids.len() as u32)
+        .ok_or(error!(ErrorCode::CustomError))?;
+    if assets_registered > event.asset_capacity() {
+        return Err(error!(ErrorCode::InvalidAssetRegistration));
+    }
+
+    let (_, slot) = TicketInventoryPage::locate(start_index);
+    let mut inventory_page = context.accounts.inventory_page.load_mut()?;
+    inventory_page.register_assets(slot, &asset_ids)?;
+
+    event.assets_registered = assets_registered;
+
+    Ok(())
+}
@@ -0,0 +1,268 @@
+//! Ticketfair limit bid instruction handlers (Dutch Auction)
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use super::shared::{transfer_lamports, transfer_tokens};
+use crate::state::{Bid, Event, User};
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct PlaceLimitBidAccountConstraints<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    /// The PDA that holds escrowed funds for the event
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = bidder,
+        space = Bid::DISCRIMINATOR.len() + Bid::INIT_SPACE,
+        seeds = [b"bid", event.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    /// Bidder's user account (required when the event caps tickets per wallet)
+    #[account(seeds = [b"user", bidder.key().as_ref()], bump = user.bump)]
+    pub user: Option<Account<'info, User>>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the place limit bid instruction by:
+// 1. Checking the Dutch auction is taking bids and the limit is below the tier's current price,
+//    but not below its end price
+// 2. Escrowing the limit price for each ticket in the bid
+// 3. Recording a standing limit bid, which a keeper turns into a real bid with execute_limit_bid
+//    once the price falls to the limit. Until then the bidder can take it back with withdraw_bid
+//    under the event's withdrawal policy, until award processing starts; one never executed is
+//    refunded in full with refund_bid once the auction is over
+pub fn place_limit_bid(
+    context: Context<PlaceLimitBidAccountConstraints>,
+    limit_price: u64,
+    quantity: u8,
+    tier: u8,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let bid = &mut context.accounts.bid;
+    let bidder = &context.accounts.bidder;
+    let event_pda = &context.accounts.event_pda;
+
+    let now = Clock::get()?.unix_timestamp;
+
+    if event.activate_if_due(now) {
+        msg!("Event auto-activated at auction start");
+    }
+
+    // Sealed bids have no price curve to wait on, and instant-buy bids need the buyer there to take the ticket
+    if event.is_sealed_bid() || event.instant_buy {
+        return Err(error!(ErrorCode::WrongAuctionType));
+    }
+    if !event.is_active_for_bidding(now) {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+    event.validate_limit_price(tier, limit_price, now)?;
+
+    if quantity == 0 || quantity > MAX_TICKETS_PER_BID {
+        return Err(error!(ErrorCode::InvalidBidQuantity));
+    }
+    let escrow_amount = limit_price
+        .checked_mul(quantity as u64)
+        .ok_or(error!(ErrorCode::CustomError))?;
+
+    // Enforce the per-wallet ticket cap
+    match &context.accounts.user {
+        Some(user) if !event.within_wallet_cap(user.tickets_held(&event.key()), quantity as u32) => {
+            return Err(error!(ErrorCode::WalletTicketCapExceeded));
+        }
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        _ => {}
+    }
+
+    // Escrow the limit price from bidder to event PDA (or its token vault)
+    if event.is_token_denominated() {
+        let (Some(payment_mint), Some(bidder_token_account), Some(event_vault), Some(token_program)) = (
+            &context.accounts.payment_mint,
+            &context.accounts.bidder_token_account,
+            &context.accounts.event_vault,
+            &context.accounts.token_program,
+        ) else {
+            return Err(error!(ErrorCode::PaymentAccountsMissing));
+        };
+        transfer_tokens(
+            bidder_token_account,
+            event_vault,
+            &escrow_amount,
+            payment_mint,
+            &bidder.to_account_info(),
+            token_program,
+            None,
+        )?;
+    } else {
+        transfer_lamports(
+            &bidder.to_account_info(),
+            &event_pda.to_account_info(),
+            escrow_amount,
+            &context.accounts.system_program,
+            None,
+        )?;
+    }
+
+    // Record the limit bid; it only takes its place in the auction once executed
+    bid.bidder = bidder.key();
+    bid.event = event.key();
+    bid.amount = limit_price;
+    bid.status = BID_STATUS_LIMIT;
+    bid.bump = context.bumps.bid;
+    bid.quantity = quantity;
+    bid.quantity_awarded = 0;
+    bid.tier = tier;
+    bid.settled = false;
+    event.bids_open = event.bids_open.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteLimitBidAccountConstraints<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub bid: Account<'info, Bid>,
+    /// The bidder, who gets back what they escrowed above the current price (anyone can run the crank)
+    #[account(mut, address = bid.bidder @ ErrorCode::CustomError)]
+    pub bidder: SystemAccount<'info>,
+    /// Event PDA (escrow authority)
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the execute limit bid crank by:
+// 1. Checking the bid is a standing limit bid and the auction is still taking bids
+// 2. Re-pricing it at the tier's current price, which must have fallen to the limit
+// 3. Entering it into the auction exactly as place_bid would have at that price
+// 4. Returning what was escrowed above the current price to the bidder
+pub fn execute_limit_bid(
+    context: Context<ExecuteLimitBidAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let bid = &mut context.accounts.bid;
+    let bidder = &context.accounts.bidder;
+    let event_pda = &context.accounts.event_pda;
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    if bid.status != BID_STATUS_LIMIT {
+        return Err(error!(ErrorCode::NotLimitBid));
+    }
+    if !event.is_active_for_bidding(now) {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+    let current_price = event.bid_price(bid.tier, bid.amount, now)?;
+    let excess = bid.execute_limit(current_price)?;
+
+    // Executed bids in the closing seconds extend the auction, the same as bids placed directly
+    if event.extend_for_bid(now) {
+        msg!("Auction extended to {}", event.extended_end_time);
+    }
+
+    bid.open(event, clock.slot)?;
+
+    // Return what was escrowed above the current price
+    if excess > 0 {
+        let event_key = event.key();
+        let bump = &[context.bumps.event_pda];
+        let event_pda_seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), bump];
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(bidder_token_account), Some(event_vault), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.bidder_token_account,
+                &context.accounts.event_vault,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                event_vault,
+                bidder_token_account,
+                &excess,
+                payment_mint,
+                &event_pda.to_account_info(),
+                token_program,
+                Some(event_pda_seeds),
+            )?;
+        } else {
+            transfer_lamports(
+                &event_pda.to_account_info(),
+                &bidder.to_account_info(),
+                excess,
+                &context.accounts.system_program,
+                Some(event_pda_seeds),
+            )?;
+        }
+    }
+
+    Ok(())
+}
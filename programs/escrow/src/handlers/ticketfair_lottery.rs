@@ -0,0 +1,136 @@
+//! Ticketfair lottery instruction handlers for oversubscribed auctions
+
+use anchor_lang::prelude::*;
+use crate::state::{draw_lottery, Bid, Event, RandomnessProvider, SwitchboardRandomness};
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct CommitLotteryAccountConstraints<'info> {
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump = event.bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+    /// Switchboard randomness account the draw will use
+    /// CHECK: Owner and layout verified by SwitchboardRandomness::try_from_account
+    pub randomness_account: UncheckedAccount<'info>,
+}
+
+// Handle the commit lottery instruction by:
+// 1. Checking bidding has closed on a Dutch auction that hasn't been finalized
+// 2. Checking the randomness was committed in the previous slot and hasn't been revealed,
+//    so nobody (including the organizer) can know the outcome yet
+// 3. Binding the event to that randomness account, so the draw can't be retried with another
+pub fn commit_lottery(
+    context: Context<CommitLotteryAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let clock = Clock::get()?;
+
+    // Sequential and instant-buy awards are first-come, so there is nothing to draw
+    if event.is_sealed_bid() || event.sequential_awards || event.instant_buy {
+        return Err(error!(ErrorCode::WrongAuctionType));
+    }
+    if event.status != EVENT_STATUS_ACTIVE {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+    if event.auction_phase(clock.unix_timestamp) != AUCTION_PHASE_CLOSED {
+        return Err(error!(ErrorCode::CustomError)); // Replace with AuctionStillRunning if desired
+    }
+    if event.has_lottery() {
+        return Err(error!(ErrorCode::CustomError)); // Replace with LotteryAlreadyCommitted if desired
+    }
+
+    let randomness = SwitchboardRandomness::try_from_account(&context.accounts.randomness_account)?;
+    if randomness.seed_slot() != clock.slot.saturating_sub(1) || randomness.is_revealed() {
+        return Err(error!(ErrorCode::InvalidRandomnessAccount));
+    }
+
+    event.lottery_randomness = context.accounts.randomness_account.key();
+    event.award_processing_started = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DrawWinnersAccountConstraints<'info> {
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump = event.bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+    /// The randomness account committed with commit_lottery
+    /// CHECK: Address checked against the event, owner and layout verified by SwitchboardRandomness::try_from_account
+    #[account(address = event.lottery_randomness @ ErrorCode::InvalidRandomnessAccount)]
+    pub randomness_account: UncheckedAccount<'info>,
+    // remaining_accounts: every pending Bid in the lottery pool (tier 0, at tier 0's end price), writable
+}
+
+// Handle the draw winners instruction by:
+// 1. Reading the revealed randomness from the committed account
+// 2. Checking every pending bid in the lottery pool was passed in exactly once; bids awarded or
+//    withdrawn before the draw have already left the pool
+// 3. Drawing as many pending bids as fit in tier 0's remaining supply, less the tickets held back
+//    for pending bids above the pool, and marking them as winners; the rest stay pending and can be
+//    refunded as usual
+pub fn draw_winners<'info>(
+    context: Context<'_, '_, 'info, 'info, DrawWinnersAccountConstraints<'info>>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let clock = Clock::get()?;
+
+    if !event.has_lottery() || event.lottery_drawn {
+        return Err(error!(ErrorCode::CustomError)); // Replace with LotteryNotCommitted if desired
+    }
+    if event.status != EVENT_STATUS_ACTIVE {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+
+    let randomness = SwitchboardRandomness::try_from_account(&context.accounts.randomness_account)?;
+    let random_value = randomness.reveal_value(clock.slot)?;
+
+    // Load the pool, rejecting anything that isn't one of this event's pending pool bids or is passed twice
+    if context.remaining_accounts.len() != event.lottery_pool_bids as usize {
+        return Err(error!(ErrorCode::LotteryEntriesMismatch));
+    }
+    let mut bids: Vec<Account<'info, Bid>> = Vec::with_capacity(context.remaining_accounts.len());
+    for bid_account in context.remaining_accounts.iter() {
+        let bid = Account::<Bid>::try_from(bid_account)?;
+        let already_passed = bids.iter().any(|other| other.key() == bid.key());
+        if bid.event != event.key() ||
+            !event.is_lottery_pool_bid(bid.tier, bid.amount) ||
+            bid.status != BID_STATUS_PENDING ||
+            already_passed
+        {
+            return Err(error!(ErrorCode::LotteryEntriesMismatch));
+        }
+        bids.push(bid);
+    }
+
+    let entries: Vec<(Pubkey, u8)> = bids
+        .iter()
+        .map(|bid| (bid.key(), bid.quantity))
+        .collect();
+    let winners = draw_lottery(&random_value, &entries, event.lottery_tickets_available());
+
+    for ((bid_key, _), won) in entries.iter().zip(winners) {
+        if !won {
+            continue;
+        }
+        if let Some(bid) = bids.iter_mut().find(|bid| bid.key() == *bid_key) {
+            bid.lottery_winner = true;
+            bid.exit(&crate::ID)?;
+        }
+    }
+
+    event.lottery_drawn = true;
+
+    Ok(())
+}
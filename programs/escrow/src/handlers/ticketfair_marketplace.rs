@@ -0,0 +1,281 @@
+//! Ticketfair resale marketplace instruction handlers
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use super::shared::{transfer_lamports, transfer_tokens};
+use super::ticketfair_ticket::move_ticket_holdings;
+use crate::state::{Event, Listing, Ticket, User};
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ListTicketAccountConstraints<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        seeds = [b"ticket", event.key().as_ref(), ticket.original_owner.as_ref()],
+        bump = ticket.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+        constraint = ticket.owner == seller.key() @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+    #[account(
+        init,
+        payer = seller,
+        space = Listing::DISCRIMINATOR.len() + Listing::INIT_SPACE,
+        seeds = [b"listing", ticket.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    /// SPL token mint the ticket is priced in (omit to sell for SOL)
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the list ticket instruction by:
+// 1. Checking the ticket was awarded rather than handed out by buy_ticket, the event is active or
+//    finalized, and its transfer policy allows the ticket to be resold at this price; capped resales must be priced in the event's payment currency, which
+//    the cap is measured in
+// 2. Saving the details of the listing to the listing account
+// 3. Moving the ticket into escrow by making the listing its owner, so it can't be transferred
+//    or released while it's for sale, and counting the listing so the event isn't closed under it
+pub fn list_ticket(
+    context: Context<ListTicketAccountConstraints>,
+    price: u64, // Asking price for the whole ticket
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let ticket = &mut context.accounts.ticket;
+    let listing = &mut context.accounts.listing;
+
+    if !ticket.can_claim() {
+        return Err(error!(ErrorCode::CustomError)); // Replace with TicketNotOwned if desired
+    }
+    if !event.can_transfer_tickets() || !ticket.awarded {
+        return Err(error!(ErrorCode::TicketNotTransferable));
+    }
+    let payment_mint = context
+        .accounts
+        .payment_mint
+        .as_ref()
+        .map_or(Pubkey::default(), |payment_mint| payment_mint.key());
+    if event.transfer_policy == TRANSFER_POLICY_CAPPED && payment_mint != event.payment_mint {
+        return Err(error!(ErrorCode::PaymentMintMismatch));
+    }
+    event.resale_royalty(price, ticket.tier, ticket.quantity)?;
+
+    listing.set_inner(Listing {
+        seller: context.accounts.seller.key(),
+        event: event.key(),
+        ticket: ticket.key(),
+        payment_mint,
+        price,
+        bump: context.bumps.listing,
+    });
+    ticket.owner = listing.key();
+    event.listings_open = event.listings_open.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelListingAccountConstraints<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        seeds = [b"ticket", event.key().as_ref(), ticket.original_owner.as_ref()],
+        bump = ticket.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"listing", ticket.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller @ ErrorCode::AccountRelationshipMismatch,
+        has_one = ticket @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+// Handle the cancel listing instruction by:
+// 1. Returning the ticket from escrow to the seller
+// 2. Closing the listing, taking it off the event's count and returning the rent to the seller
+pub fn cancel_listing(
+    context: Context<CancelListingAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    context.accounts.ticket.owner = context.accounts.seller.key();
+    event.listings_open = event.listings_open.checked_sub(1).ok_or(error!(ErrorCode::CustomError))?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BuyListingAccountConstraints<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// The seller, who is paid the price less the royalty and gets the listing's rent back
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    /// The event organizer, who is paid the royalty
+    #[account(mut, address = event.organizer @ ErrorCode::CustomError)]
+    pub organizer: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"ticket", event.key().as_ref(), ticket.original_owner.as_ref()],
+        bump = ticket.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"listing", ticket.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller @ ErrorCode::AccountRelationshipMismatch,
+        has_one = ticket @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub listing: Account<'info, Listing>,
+    /// Seller's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", seller.key().as_ref()], bump = seller_user.bump)]
+    pub seller_user: Option<Account<'info, User>>,
+    /// Buyer's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", buyer.key().as_ref()], bump = buyer_user.bump)]
+    pub buyer_user: Option<Account<'info, User>>,
+    /// Mint the listing is priced in (omit for listings priced in SOL)
+    #[account(
+        address = listing.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub buyer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = seller,
+        associated_token::token_program = token_program
+    )]
+    pub seller_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = organizer,
+        associated_token::token_program = token_program
+    )]
+    pub organizer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the buy listing instruction by:
+// 1. Checking the price is no more than the buyer agreed to, and that the event's status and transfer
+//    policy (including its resale cap, which may have been set since the ticket was listed) still allow
+//    the sale; a listing for an event that has since failed can only be cancelled, as can one for a ticket
+//    handed out by buy_ticket rather than awarded
+// 2. Moving the tickets between the two wallets' holdings, enforcing the per-wallet cap
+// 3. Paying the organizer's royalty and the rest of the price to the seller from the buyer
+// 4. Releasing the ticket from escrow to the buyer, and closing the listing and taking it off the
+//    event's count
+pub fn buy_listing(
+    context: Context<BuyListingAccountConstraints>,
+    max_price: u64, // Most the buyer will pay, so the ticket can't be relisted at a higher price under them
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let ticket = &mut context.accounts.ticket;
+    let listing = &context.accounts.listing;
+    let buyer = &context.accounts.buyer;
+    let seller = &context.accounts.seller;
+
+    if listing.price > max_price {
+        return Err(error!(ErrorCode::ListingPriceAboveMax));
+    }
+    if buyer.key() == seller.key() {
+        return Err(error!(ErrorCode::CustomError)); // Replace with specific error if desired
+    }
+    if !event.can_transfer_tickets() || !ticket.awarded {
+        return Err(error!(ErrorCode::TicketNotTransferable));
+    }
+    let royalty = event.resale_royalty(listing.price, ticket.tier, ticket.quantity)?;
+
+    // Move the tickets between the two wallets' holdings
+    move_ticket_holdings(
+        event,
+        ticket.quantity,
+        &mut context.accounts.seller_user,
+        &mut context.accounts.buyer_user,
+    )?;
+
+    // The buyer pays the royalty to the organizer and the rest to the seller
+    let payments = [
+        (royalty, context.accounts.organizer.to_account_info(), &context.accounts.organizer_token_account),
+        (listing.price - royalty, seller.to_account_info(), &context.accounts.seller_token_account),
+    ];
+    for (amount, recipient, recipient_token_account) in payments {
+        if amount == 0 {
+            continue;
+        }
+        if listing.is_token_denominated() {
+            let (Some(payment_mint), Some(buyer_token_account), Some(recipient_token_account), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.buyer_token_account,
+                recipient_token_account,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                buyer_token_account,
+                recipient_token_account,
+                &amount,
+                payment_mint,
+                &buyer.to_account_info(),
+                token_program,
+                None,
+            )?;
+        } else {
+            transfer_lamports(
+                &buyer.to_account_info(),
+                &recipient,
+                amount,
+                &context.accounts.system_program,
+                None,
+            )?;
+        }
+    }
+
+    ticket.owner = buyer.key();
+    event.listings_open = event.listings_open.checked_sub(1).ok_or(error!(ErrorCode::CustomError))?;
+
+    // Bubblegum transfers of the ticket's assets go here once the integration is enabled, as in award_ticket
+    #[cfg(not(feature = "bubblegum"))]
+    msg!("Bubblegum feature not enabled - simulating cNFT transfer for asset ID: {}", ticket.cnft_asset_id);
+
+    Ok(())
+}
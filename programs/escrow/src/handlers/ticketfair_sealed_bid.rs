@@ -0,0 +1,398 @@
+//! Ticketfair sealed-bid (commit-reveal) auction instruction handlers
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use super::shared::{transfer_lamports, transfer_tokens};
+use crate::state::{sealed_bid_winners, Bid, Event, User};
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ConfigureSealedBidAccountConstraints<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// Handle the configure sealed bid instruction by:
+// 1. Checking the event hasn't been activated yet
+// 2. Switching the event to a sealed-bid auction: bids are committed between auction_start_time
+//    and auction_end_time, then revealed until reveal_end_time
+// 3. Settling at a uniform clearing price, so the settle_bid crank is enabled
+pub fn configure_sealed_bid(
+    context: Context<ConfigureSealedBidAccountConstraints>,
+    reveal_end_time: i64,
+    deposit: u64,
+    unrevealed_slash_bps: u16,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+
+    // The auction type can only be changed before the event is activated
+    if event.status != EVENT_STATUS_CREATED {
+        return Err(error!(ErrorCode::EventAlreadyActivated));
+    }
+
+    event.auction_type = AUCTION_TYPE_SEALED;
+    event.reveal_end_time = reveal_end_time;
+    event.sealed_deposit = deposit;
+    event.unrevealed_slash_bps = unrevealed_slash_bps;
+    event.uniform_price = true;
+    event.validate_params()?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitBidAccountConstraints<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    /// The PDA that holds escrowed deposits for the event
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = bidder,
+        space = Bid::DISCRIMINATOR.len() + Bid::INIT_SPACE,
+        seeds = [b"bid", event.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    /// Bidder's user account (required when the event caps tickets per wallet)
+    #[account(seeds = [b"user", bidder.key().as_ref()], bump = user.bump)]
+    pub user: Option<Account<'info, User>>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the commit bid instruction by:
+// 1. Checking the sealed-bid auction is in its commit phase
+// 2. Escrowing the deposit for each ticket in the bid
+// 3. Recording the commitment, keeping the bid amount hidden until the reveal phase, and the slash
+//    owed from the deposit if the bid is never revealed
+pub fn commit_bid(
+    context: Context<CommitBidAccountConstraints>,
+    commitment: [u8; 32],
+    quantity: u8,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let bid = &mut context.accounts.bid;
+    let bidder = &context.accounts.bidder;
+    let event_pda = &context.accounts.event_pda;
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    if event.activate_if_due(now) {
+        msg!("Event auto-activated at auction start");
+    }
+
+    if !event.is_sealed_bid() {
+        return Err(error!(ErrorCode::WrongAuctionType));
+    }
+    if event.status != EVENT_STATUS_ACTIVE {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+    if event.auction_phase(now) != AUCTION_PHASE_COMMIT {
+        return Err(error!(ErrorCode::SealedBidPhaseMismatch));
+    }
+
+    if quantity == 0 || quantity > MAX_TICKETS_PER_BID {
+        return Err(error!(ErrorCode::InvalidBidQuantity));
+    }
+    let deposit_amount = event
+        .sealed_deposit
+        .checked_mul(quantity as u64)
+        .ok_or(error!(ErrorCode::CustomError))?;
+
+    // Enforce the per-wallet ticket cap
+    match &context.accounts.user {
+        Some(user) if !event.within_wallet_cap(user.tickets_held(&event.key()), quantity as u32) => {
+            return Err(error!(ErrorCode::WalletTicketCapExceeded));
+        }
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        _ => {}
+    }
+
+    // Escrow the deposit from bidder to event PDA (or its token vault)
+    if event.is_token_denominated() {
+        let (Some(payment_mint), Some(bidder_token_account), Some(event_vault), Some(token_program)) = (
+            &context.accounts.payment_mint,
+            &context.accounts.bidder_token_account,
+            &context.accounts.event_vault,
+            &context.accounts.token_program,
+        ) else {
+            return Err(error!(ErrorCode::PaymentAccountsMissing));
+        };
+        transfer_tokens(
+            bidder_token_account,
+            event_vault,
+            &deposit_amount,
+            payment_mint,
+            &bidder.to_account_info(),
+            token_program,
+            None,
+        )?;
+    } else {
+        transfer_lamports(
+            &bidder.to_account_info(),
+            &event_pda.to_account_info(),
+            deposit_amount,
+            &context.accounts.system_program,
+            None,
+        )?;
+    }
+
+    // Record the sealed bid; amount is the per-ticket deposit until the bid is revealed
+    bid.bidder = bidder.key();
+    bid.event = event.key();
+    bid.amount = event.sealed_deposit;
+    bid.status = BID_STATUS_COMMITTED;
+    bid.bump = context.bumps.bid;
+    bid.quantity = quantity;
+    bid.quantity_awarded = 0;
+    bid.tier = 0;
+    bid.settled = false;
+    bid.commitment = commitment;
+    bid.sequence = event.next_bid_sequence()?;
+    bid.slot = clock.slot;
+    event.bids_open = event.bids_open.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+    event.unrevealed_slashes = event
+        .unrevealed_slashes
+        .checked_add(event.unrevealed_slash(deposit_amount))
+        .ok_or(error!(ErrorCode::CustomError))?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealBidAccountConstraints<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    /// The PDA that holds escrowed deposits for the event
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+        has_one = bidder @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub bid: Account<'info, Bid>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the reveal bid instruction by:
+// 1. Checking the sealed-bid auction is in its reveal phase
+// 2. Checking the amount and salt match the bid's commitment
+// 3. Escrowing the rest of the bid on top of the deposit, after which the bid can be awarded
+// 4. Dropping the deposit's slash from the event, as a revealed bid is never slashed, and counting
+//    the bid for rank_sealed_bids
+pub fn reveal_bid(
+    context: Context<RevealBidAccountConstraints>,
+    amount: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let bid = &mut context.accounts.bid;
+    let bidder = &context.accounts.bidder;
+    let event_pda = &context.accounts.event_pda;
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    if event.status != EVENT_STATUS_ACTIVE {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+    if event.auction_phase(now) != AUCTION_PHASE_REVEAL {
+        return Err(error!(ErrorCode::SealedBidPhaseMismatch));
+    }
+    if bid.status != BID_STATUS_COMMITTED {
+        return Err(error!(ErrorCode::CustomError)); // Replace with BidNotCommitted if desired
+    }
+    if Bid::sealed_commitment(amount, &salt) != bid.commitment {
+        return Err(error!(ErrorCode::CommitmentMismatch));
+    }
+
+    // The deposit doubles as the reserve price, so a valid bid never needs part of it back
+    if amount < event.sealed_deposit {
+        return Err(error!(ErrorCode::CustomError)); // Replace with BidBelowReserve if desired
+    }
+    let top_up = (amount - event.sealed_deposit)
+        .checked_mul(bid.quantity as u64)
+        .ok_or(error!(ErrorCode::CustomError))?;
+
+    if top_up > 0 {
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(bidder_token_account), Some(event_vault), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.bidder_token_account,
+                &context.accounts.event_vault,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                bidder_token_account,
+                event_vault,
+                &top_up,
+                payment_mint,
+                &bidder.to_account_info(),
+                token_program,
+                None,
+            )?;
+        } else {
+            transfer_lamports(
+                &bidder.to_account_info(),
+                &event_pda.to_account_info(),
+                top_up,
+                &context.accounts.system_program,
+                None,
+            )?;
+        }
+    }
+
+    // A revealed bid is an ordinary pending bid for its full amount
+    let deposit = bid.total_amount().ok_or(error!(ErrorCode::CustomError))?;
+    event.unrevealed_slashes = event.unrevealed_slashes.saturating_sub(event.unrevealed_slash(deposit));
+    event.sealed_bids_revealed = event.sealed_bids_revealed.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+    bid.amount = amount;
+    bid.status = BID_STATUS_PENDING;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RankSealedBidsAccountConstraints<'info> {
+    pub organizer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", organizer.key().as_ref()],
+        bump = event.bump,
+        constraint = event.organizer == organizer.key() @ ErrorCode::CustomError,
+    )]
+    pub event: Account<'info, Event>,
+    // remaining_accounts: every revealed Bid for the event, writable
+}
+
+// Handle the rank sealed bids instruction by:
+// 1. Checking the reveal window has closed and the bids haven't been ranked yet
+// 2. Checking every revealed bid was passed in exactly once
+// 3. Marking the highest pending bids that fit in the supply as winners, so award_ticket can
+//    only award bids at or above the clearing price and never passes over a higher bid
+pub fn rank_sealed_bids<'info>(
+    context: Context<'_, '_, 'info, 'info, RankSealedBidsAccountConstraints<'info>>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let clock = Clock::get()?;
+
+    if !event.is_sealed_bid() {
+        return Err(error!(ErrorCode::WrongAuctionType));
+    }
+    if event.status != EVENT_STATUS_ACTIVE {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+    if event.auction_phase(clock.unix_timestamp) != AUCTION_PHASE_CLOSED || event.sealed_bids_ranked {
+        return Err(error!(ErrorCode::SealedBidPhaseMismatch));
+    }
+
+    // Load the revealed bids, rejecting anything that isn't one of this event's revealed bids or is passed twice
+    if context.remaining_accounts.len() != event.sealed_bids_revealed as usize {
+        return Err(error!(ErrorCode::SealedBidEntriesMismatch));
+    }
+    let mut bids: Vec<Account<'info, Bid>> = Vec::with_capacity(context.remaining_accounts.len());
+    for bid_account in context.remaining_accounts.iter() {
+        let bid = Account::<Bid>::try_from(bid_account)?;
+        let already_passed = bids.iter().any(|other| other.key() == bid.key());
+        if bid.event != event.key() || bid.status == BID_STATUS_COMMITTED || already_passed {
+            return Err(error!(ErrorCode::SealedBidEntriesMismatch));
+        }
+        bids.push(bid);
+    }
+
+    // Only pending bids are ranked
+    let pending: Vec<&mut Account<'info, Bid>> = bids
+        .iter_mut()
+        .filter(|bid| bid.status == BID_STATUS_PENDING)
+        .collect();
+    let entries: Vec<(u64, u64, u8)> = pending
+        .iter()
+        .map(|bid| (bid.amount, bid.sequence, bid.quantity))
+        .collect();
+    let winners = sealed_bid_winners(&entries, event.ticket_supply.saturating_sub(event.tickets_awarded));
+
+    for (bid, won) in pending.into_iter().zip(winners) {
+        if won {
+            bid.sealed_winner = true;
+            bid.exit(&crate::ID)?;
+        }
+    }
+
+    event.sealed_bids_ranked = true;
+    event.award_processing_started = true;
+
+    Ok(())
+}
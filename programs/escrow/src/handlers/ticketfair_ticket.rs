@@ -1,13 +1,20 @@
 //! Ticketfair ticket instruction handlers
 
 use anchor_lang::prelude::*;
-use crate::state::{Ticket, Event};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use super::shared::{transfer_lamports, transfer_tokens};
+use crate::state::{Ticket, Event, User};
+use crate::error::ErrorCode;
 
 #[derive(Accounts)]
 pub struct BuyTicketAccountConstraints<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
     pub event: Account<'info, Event>,
     #[account(
         init,
@@ -17,6 +24,9 @@ pub struct BuyTicketAccountConstraints<'info> {
         bump
     )]
     pub ticket: Account<'info, Ticket>,
+    /// Buyer's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", buyer.key().as_ref()], bump = user.bump)]
+    pub user: Option<Account<'info, User>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -24,11 +34,204 @@ pub fn buy_ticket(
     context: Context<BuyTicketAccountConstraints>,
     offchain_ref: String,
 ) -> Result<()> {
+    let event = &context.accounts.event;
     let ticket = &mut context.accounts.ticket;
+
+    // Enforce the per-wallet ticket cap and record the buyer's holdings
+    let event_key = event.key();
+    match &mut context.accounts.user {
+        Some(user) => {
+            if !event.within_wallet_cap(user.tickets_held(&event_key), 1) {
+                return Err(error!(ErrorCode::WalletTicketCapExceeded));
+            }
+            user.record_tickets(&event_key, 1)?;
+        }
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        None => {}
+    }
+
     ticket.owner = context.accounts.buyer.key();
+    ticket.original_owner = context.accounts.buyer.key();
     ticket.event = context.accounts.event.key();
     ticket.status = 0;
     ticket.offchain_ref = offchain_ref;
     ticket.bump = context.bumps.ticket;
+    ticket.quantity = 1;
     Ok(())
-} 
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct TransferTicketAccountConstraints<'info> {
+    /// The ticket holder, who is paid the resale price less the royalty
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// The wallet receiving the ticket, who pays the resale price
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+    #[account(
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    /// The event organizer, who is paid the royalty
+    #[account(mut, address = event.organizer @ ErrorCode::CustomError)]
+    pub organizer: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"ticket", event.key().as_ref(), ticket.original_owner.as_ref()],
+        bump = ticket.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+        has_one = owner @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+    /// Holder's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", owner.key().as_ref()], bump = owner_user.bump)]
+    pub owner_user: Option<Account<'info, User>>,
+    /// New owner's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", new_owner.key().as_ref()], bump = new_owner_user.bump)]
+    pub new_owner_user: Option<Account<'info, User>>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = new_owner,
+        associated_token::token_program = token_program
+    )]
+    pub new_owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = organizer,
+        associated_token::token_program = token_program
+    )]
+    pub organizer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the transfer ticket instruction by:
+// 1. Checking the ticket was awarded rather than handed out by buy_ticket, the event is active or
+//    finalized, and its transfer policy allows the ticket to change hands at this price
+// 2. Moving the ticket between the two wallets' holdings, enforcing the per-wallet cap
+// 3. Paying the organizer's royalty and the rest of the price to the holder from the new owner
+// 4. Reassigning the ticket; its address stays derived from the wallet it was issued to
+pub fn transfer_ticket(
+    context: Context<TransferTicketAccountConstraints>,
+    price: u64, // Total price for the ticket, in the event's payment currency (0 for a gift)
+) -> Result<()> {
+    let event = &context.accounts.event;
+    let ticket = &mut context.accounts.ticket;
+    let owner = &context.accounts.owner;
+    let new_owner = &context.accounts.new_owner;
+
+    if !ticket.can_claim() {
+        return Err(error!(ErrorCode::CustomError)); // Replace with TicketNotOwned if desired
+    }
+    if new_owner.key() == owner.key() {
+        return Err(error!(ErrorCode::CustomError)); // Replace with specific error if desired
+    }
+    if !event.can_transfer_tickets() || !ticket.awarded {
+        return Err(error!(ErrorCode::TicketNotTransferable));
+    }
+    let royalty = event.resale_royalty(price, ticket.tier, ticket.quantity)?;
+
+    // Move the tickets between the two wallets' holdings
+    move_ticket_holdings(
+        event,
+        ticket.quantity,
+        &mut context.accounts.owner_user,
+        &mut context.accounts.new_owner_user,
+    )?;
+
+    // The new owner pays the royalty to the organizer and the rest to the holder
+    let payments = [
+        (royalty, context.accounts.organizer.to_account_info(), &context.accounts.organizer_token_account),
+        (price - royalty, owner.to_account_info(), &context.accounts.owner_token_account),
+    ];
+    for (amount, recipient, recipient_token_account) in payments {
+        if amount == 0 {
+            continue;
+        }
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(new_owner_token_account), Some(recipient_token_account), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.new_owner_token_account,
+                recipient_token_account,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                new_owner_token_account,
+                recipient_token_account,
+                &amount,
+                payment_mint,
+                &new_owner.to_account_info(),
+                token_program,
+                None,
+            )?;
+        } else {
+            transfer_lamports(
+                &new_owner.to_account_info(),
+                &recipient,
+                amount,
+                &context.accounts.system_program,
+                None,
+            )?;
+        }
+    }
+
+    ticket.owner = new_owner.key();
+
+    // Bubblegum transfers of the ticket's assets go here once the integration is enabled, as in award_ticket
+    #[cfg(not(feature = "bubblegum"))]
+    msg!("Bubblegum feature not enabled - simulating cNFT transfer for asset ID: {}", ticket.cnft_asset_id);
+
+    Ok(())
+}
+
+/// Move a ticket's admissions from one wallet's event holdings to another's, enforcing the
+/// per-wallet cap on the receiving wallet (both user accounts are required when the event has a cap)
+pub(crate) fn move_ticket_holdings(
+    event: &Account<Event>,
+    quantity: u8,
+    from_user: &mut Option<Account<User>>,
+    to_user: &mut Option<Account<User>>,
+) -> Result<()> {
+    let event_key = event.key();
+    let quantity = quantity as u32;
+    match from_user {
+        Some(from_user) => from_user.release_tickets(&event_key, quantity),
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        None => {}
+    }
+    match to_user {
+        Some(to_user) => {
+            if !event.within_wallet_cap(to_user.tickets_held(&event_key), quantity) {
+                return Err(error!(ErrorCode::WalletTicketCapExceeded));
+            }
+            to_user.record_tickets(&event_key, quantity)?;
+        }
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        None => {}
+    }
+    Ok(())
+}
@@ -1,7 +1,9 @@
 //! Ticketfair user instruction handlers
 
 use anchor_lang::prelude::*;
+use super::shared::transfer_lamports;
 use crate::state::User;
+use crate::error::ErrorCode;
 
 #[derive(Accounts)]
 pub struct CreateUserAccountConstraints<'info> {
@@ -26,5 +28,55 @@ pub fn create_user(
     user.tickets_purchased = 0;
     user.events_created = 0;
     user.bump = context.bumps.user;
+    user.event_holdings = Vec::new();
     Ok(())
-} 
\ No newline at end of file
+} 
+
+#[derive(Accounts)]
+pub struct MigrateUserAccountConstraints<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: A user account created before its layout grew, which can't be deserialized as a
+    /// User until it has been resized. The seeds tie it to the authority and the handler checks
+    /// its discriminator.
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump,
+        owner = crate::ID @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub user: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the migrate user instruction by:
+// 1. Checking the account really is a user account
+// 2. Topping up its rent from the authority and growing it to the current User size; the new
+//    bytes are zeroed, so fields added at the end (event_holdings) start out empty
+// 3. Checking the resized account deserializes as a User
+pub fn migrate_user(
+    context: Context<MigrateUserAccountConstraints>,
+) -> Result<()> {
+    let user = context.accounts.user.to_account_info();
+    let space = User::DISCRIMINATOR.len() + User::INIT_SPACE;
+
+    if !user.try_borrow_data()?.starts_with(User::DISCRIMINATOR) {
+        return Err(error!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch));
+    }
+    if user.data_len() < space {
+        let rent_due = Rent::get()?.minimum_balance(space).saturating_sub(user.lamports());
+        if rent_due > 0 {
+            transfer_lamports(
+                &context.accounts.authority.to_account_info(),
+                &user,
+                rent_due,
+                &context.accounts.system_program,
+                None,
+            )?;
+        }
+        user.realloc(space, true)?;
+    }
+
+    User::try_deserialize(&mut &user.try_borrow_data()?[..])?;
+    Ok(())
+}
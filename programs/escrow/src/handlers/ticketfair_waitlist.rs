@@ -0,0 +1,275 @@
+//! Ticketfair waitlist instruction handlers for sold-out tiers
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{allocate, assign, Allocate, Assign};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use super::shared::{transfer_lamports, transfer_tokens};
+use crate::state::{Bid, Event, Ticket, User};
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ReleaseTicketAccountConstraints<'info> {
+    /// The ticket holder returning their ticket, or the organizer revoking it
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"event", event.organizer.as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        mut,
+        seeds = [b"ticket", event.key().as_ref(), ticket.original_owner.as_ref()],
+        bump = ticket.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+    /// The bid the ticket was awarded to
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), ticket.original_owner.as_ref()],
+        bump = bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub bid: Account<'info, Bid>,
+    /// The ticket holder, who gets back what they paid for the ticket
+    #[account(mut, address = ticket.owner @ ErrorCode::CustomError)]
+    pub bidder: SystemAccount<'info>,
+    /// Ticket holder's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", ticket.owner.as_ref()], bump = bidder_user.bump)]
+    pub bidder_user: Option<Account<'info, User>>,
+    /// The bid at the head of the ticket tier's waitlist (omit when nobody is waiting)
+    #[account(
+        mut,
+        seeds = [b"bid", event.key().as_ref(), promoted_bid.bidder.as_ref()],
+        bump = promoted_bid.bump,
+        has_one = event @ ErrorCode::AccountRelationshipMismatch,
+    )]
+    pub promoted_bid: Option<Account<'info, Bid>>,
+    /// Address of the promoted bidder's ticket, created by the handler (omit when nobody is waiting)
+    #[account(mut)]
+    pub promoted_ticket: Option<SystemAccount<'info>>,
+    /// Promoted bidder's user account (required when the event caps tickets per wallet)
+    #[account(mut, seeds = [b"user", promoted_user.authority.as_ref()], bump = promoted_user.bump)]
+    pub promoted_user: Option<Account<'info, User>>,
+    /// Event PDA (escrow authority)
+    #[account(mut, seeds = [b"escrow", event.key().as_ref()], bump)]
+    pub event_pda: SystemAccount<'info>,
+    /// Payment mint for token-denominated events (omit for SOL events)
+    #[account(
+        address = event.payment_mint @ ErrorCode::PaymentMintMismatch,
+        mint::token_program = token_program
+    )]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Token vault owned by the escrow PDA
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = event_pda,
+        associated_token::token_program = token_program
+    )]
+    pub event_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Handle the release ticket instruction by:
+// 1. Checking the ticket is being returned by its holder (or revoked by the organizer) while bidding is still open,
+//    and is still held by the wallet it was issued to, since the refund comes out of that wallet's bid
+// 2. Checking the promoted bid is the next one on the ticket tier's waitlist and bid for at least as many tickets,
+//    or that nobody is waiting if no bid is promoted
+// 3. Refunding the holder what they paid for the ticket and taking it off their bid
+// 4. Awarding the ticket's admissions to the promoted bid and reissuing the ticket, with the same cNFT assets,
+//    to the promoted bidder, paying for the new ticket with the released ticket's rent; or, with nobody
+//    waiting, returning the admissions to the tier so they can be awarded again
+pub fn release_ticket(
+    context: Context<ReleaseTicketAccountConstraints>,
+) -> Result<()> {
+    let event = &mut context.accounts.event;
+    let ticket = &mut context.accounts.ticket;
+    let bid = &mut context.accounts.bid;
+    let authority = &context.accounts.authority;
+
+    // Only the ticket holder or the organizer can release a ticket
+    if authority.key() != ticket.owner && authority.key() != event.organizer {
+        return Err(error!(ErrorCode::CustomError)); // Replace with specific error if desired
+    }
+
+    // Tickets can only change hands this way until the auction is finalized
+    if event.status != EVENT_STATUS_ACTIVE {
+        return Err(error!(ErrorCode::AuctionNotActive));
+    }
+    if !ticket.can_refund() {
+        return Err(error!(ErrorCode::CustomError)); // Replace with TicketNotOwned if desired
+    }
+    if ticket.has_changed_hands() {
+        return Err(error!(ErrorCode::TicketChangedHands));
+    }
+
+    // A released ticket goes to the head of its tier's waitlist, or back to the tier if nobody is waiting
+    let ticket_tier = event
+        .tiers
+        .get_mut(ticket.tier as usize)
+        .ok_or(error!(ErrorCode::InvalidTicketTier))?;
+    let promoted = match (&mut context.accounts.promoted_bid, &context.accounts.promoted_ticket) {
+        (Some(promoted_bid), Some(promoted_ticket)) => {
+            if !ticket_tier.has_waitlist() {
+                return Err(error!(ErrorCode::WaitlistEmpty));
+            }
+            if promoted_bid.status != BID_STATUS_WAITLISTED ||
+                promoted_bid.tier != ticket.tier ||
+                promoted_bid.waitlist_position != ticket_tier.waitlist_head
+            {
+                return Err(error!(ErrorCode::WaitlistOutOfOrder));
+            }
+            if promoted_bid.quantity < ticket.quantity {
+                return Err(error!(ErrorCode::WaitlistBidTooSmall));
+            }
+            ticket_tier.waitlist_head += 1;
+            Some((promoted_bid, promoted_ticket))
+        }
+        (None, None) if !ticket_tier.has_waitlist() => None,
+        _ => return Err(error!(ErrorCode::WaitlistOutOfOrder)),
+    };
+
+    // Move the tickets between the two wallets' holdings
+    let event_key = event.key();
+    let quantity = ticket.quantity;
+    match &mut context.accounts.bidder_user {
+        Some(bidder_user) => bidder_user.release_tickets(&event_key, quantity as u32),
+        None if event.max_tickets_per_wallet > 0 => {
+            return Err(error!(ErrorCode::UserAccountRequired));
+        }
+        None => {}
+    }
+    if let Some((promoted_bid, _)) = &promoted {
+        match &mut context.accounts.promoted_user {
+            Some(promoted_user) => {
+                if promoted_user.authority != promoted_bid.bidder {
+                    return Err(error!(ErrorCode::AccountRelationshipMismatch));
+                }
+                if !event.within_wallet_cap(promoted_user.tickets_held(&event_key), quantity as u32) {
+                    return Err(error!(ErrorCode::WalletTicketCapExceeded));
+                }
+                promoted_user.record_tickets(&event_key, quantity as u32)?;
+            }
+            None if event.max_tickets_per_wallet > 0 => {
+                return Err(error!(ErrorCode::UserAccountRequired));
+            }
+            None => {}
+        }
+    }
+
+    // Take the tickets off the holder's bid, refunding what they paid for them
+    let refund_amount = bid.release_tickets(quantity)?;
+    ticket.status = TICKET_STATUS_REFUNDED;
+
+    match promoted {
+        Some((promoted_bid, promoted_ticket)) => {
+            // The promoted bid is awarded the released tickets; any more it bid for are refunded at settlement
+            promoted_bid.status = BID_STATUS_AWARDED;
+            promoted_bid.quantity_awarded = quantity;
+            event.tiers[ticket.tier as usize].record_award_price(promoted_bid.amount);
+
+            // The released ticket's rent moves to the promoted bidder's ticket address, then the new
+            // ticket is allocated there, so whoever releases the ticket doesn't pay for its replacement
+            let (promoted_ticket_address, promoted_ticket_bump) = Pubkey::find_program_address(
+                &[b"ticket", event_key.as_ref(), promoted_bid.bidder.as_ref()],
+                &crate::ID,
+            );
+            if promoted_ticket.key() != promoted_ticket_address {
+                return Err(error!(ErrorCode::AccountRelationshipMismatch));
+            }
+            ticket.close(promoted_ticket.to_account_info())?;
+            let promoted_ticket_seeds: &[&[u8]] = &[
+                b"ticket",
+                event_key.as_ref(),
+                promoted_bid.bidder.as_ref(),
+                &[promoted_ticket_bump],
+            ];
+            allocate(
+                CpiContext::new_with_signer(
+                    context.accounts.system_program.to_account_info(),
+                    Allocate { account_to_allocate: promoted_ticket.to_account_info() },
+                    &[promoted_ticket_seeds],
+                ),
+                (Ticket::DISCRIMINATOR.len() + Ticket::INIT_SPACE) as u64,
+            )?;
+            assign(
+                CpiContext::new_with_signer(
+                    context.accounts.system_program.to_account_info(),
+                    Assign { account_to_assign: promoted_ticket.to_account_info() },
+                    &[promoted_ticket_seeds],
+                ),
+                &crate::ID,
+            )?;
+            let reissued_ticket = Ticket {
+                owner: promoted_bid.bidder,
+                event: event_key,
+                status: TICKET_STATUS_OWNED,
+                offchain_ref: String::new(), // To be set by user later
+                bump: promoted_ticket_bump,
+                cnft_asset_id: ticket.cnft_asset_id,
+                quantity,
+                tier: ticket.tier,
+                inventory_index: ticket.inventory_index,
+                original_owner: promoted_bid.bidder,
+                awarded: true,
+            };
+            reissued_ticket.try_serialize(&mut &mut promoted_ticket.try_borrow_mut_data()?[..])?;
+
+            // Bubblegum transfers of the ticket's assets go here once the integration is enabled, as in award_ticket
+            #[cfg(not(feature = "bubblegum"))]
+            msg!("Bubblegum feature not enabled - simulating cNFT transfer for asset ID: {}", ticket.cnft_asset_id);
+        }
+        None => {
+            // The refunded ticket stays behind as a record; the tier's awarded count drops so the
+            // admissions can be awarded again, with new assets
+            event.return_tickets(ticket.tier, quantity)?;
+        }
+    }
+
+    if refund_amount > 0 {
+        let bidder = &context.accounts.bidder;
+        let event_pda = &context.accounts.event_pda;
+        let bump = &[context.bumps.event_pda];
+        let event_pda_seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), bump];
+        if event.is_token_denominated() {
+            let (Some(payment_mint), Some(bidder_token_account), Some(event_vault), Some(token_program)) = (
+                &context.accounts.payment_mint,
+                &context.accounts.bidder_token_account,
+                &context.accounts.event_vault,
+                &context.accounts.token_program,
+            ) else {
+                return Err(error!(ErrorCode::PaymentAccountsMissing));
+            };
+            transfer_tokens(
+                event_vault,
+                bidder_token_account,
+                &refund_amount,
+                payment_mint,
+                &event_pda.to_account_info(),
+                token_program,
+                Some(event_pda_seeds),
+            )?;
+        } else {
+            transfer_lamports(
+                &event_pda.to_account_info(),
+                &bidder.to_account_info(),
+                refund_amount,
+                &context.accounts.system_program,
+                Some(event_pda_seeds),
+            )?;
+        }
+    }
+
+    Ok(())
+}
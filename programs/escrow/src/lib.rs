@@ -8,6 +8,7 @@ pub mod state;
 
 use anchor_lang::prelude::*;
 use handlers::*;
+use state::{Payee, SkippedBid};
 
 declare_id!("3XCMusDvagK9wyRaHEMbrhLPQfFQPXhQXZZ7oZ2pr2ah");
 
@@ -36,6 +37,7 @@ pub mod escrow {
     }
 
     /// Create a new Ticketfair event.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_event(
         context: Context<CreateEventAccountConstraints>,
         metadata_url: String,
@@ -44,6 +46,9 @@ pub mod escrow {
         end_price: u64,
         auction_start_time: i64,
         auction_end_time: i64,
+        min_tickets_sold: u32,
+        max_tickets_per_wallet: u32,
+        auto_activate: bool,
     ) -> Result<()> {
         handlers::ticketfair_event::create_event(
             context, 
@@ -52,7 +57,10 @@ pub mod escrow {
             start_price, 
             end_price, 
             auction_start_time, 
-            auction_end_time
+            auction_end_time,
+            min_tickets_sold,
+            max_tickets_per_wallet,
+            auto_activate
         )
     }
 
@@ -64,6 +72,37 @@ pub mod escrow {
         handlers::ticketfair_ticket::buy_ticket(context, offchain_ref)
     }
 
+    /// Transfer or resell a Ticketfair ticket under the event's transfer policy, paying the organizer's royalty.
+    pub fn transfer_ticket(
+        context: Context<TransferTicketAccountConstraints>,
+        price: u64,
+    ) -> Result<()> {
+        handlers::ticketfair_ticket::transfer_ticket(context, price)
+    }
+
+    /// List a Ticketfair ticket for resale, holding it in escrow until it's bought or the listing is cancelled.
+    pub fn list_ticket(
+        context: Context<ListTicketAccountConstraints>,
+        price: u64,
+    ) -> Result<()> {
+        handlers::ticketfair_marketplace::list_ticket(context, price)
+    }
+
+    /// Cancel a Ticketfair ticket listing, returning the ticket to the seller.
+    pub fn cancel_listing(
+        context: Context<CancelListingAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_marketplace::cancel_listing(context)
+    }
+
+    /// Buy a listed Ticketfair ticket, paying the seller and the organizer's royalty.
+    pub fn buy_listing(
+        context: Context<BuyListingAccountConstraints>,
+        max_price: u64,
+    ) -> Result<()> {
+        handlers::ticketfair_marketplace::buy_listing(context, max_price)
+    }
+
     /// Create a new Ticketfair user account.
     pub fn create_user(
         context: Context<CreateUserAccountConstraints>,
@@ -71,6 +110,13 @@ pub mod escrow {
         handlers::ticketfair_user::create_user(context)
     }
 
+    /// Resize a Ticketfair user account created before event holdings were tracked.
+    pub fn migrate_user(
+        context: Context<MigrateUserAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_user::migrate_user(context)
+    }
+
     /// Activate a Ticketfair event (change status from Created to Active).
     pub fn activate_event(
         context: Context<ActivateEventAccountConstraints>,
@@ -78,28 +124,214 @@ pub mod escrow {
         handlers::ticketfair_event::activate_event(context)
     }
 
-    /// Finalize a Ticketfair auction and set the closing price.
+    /// Activate an auto-activating Ticketfair event once its auction start time is reached.
+    /// Anyone can call this.
+    pub fn activate_if_due(
+        context: Context<ActivateIfDueAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_event::activate_if_due(context)
+    }
+
+    /// Finalize a Ticketfair auction and set the closing price of each ticket tier.
+    /// Moves the event to Failed instead if the minimum ticket sales were not reached.
     pub fn finalize_auction(
         context: Context<FinalizeEventAccountConstraints>,
         close_price: u64,
+        tier_close_prices: Vec<u64>,
+    ) -> Result<()> {
+        handlers::ticketfair_event::finalize_auction(context, close_price, tier_close_prices)
+    }
+
+    /// Configure anti-sniping extensions for a Ticketfair auction before activation.
+    pub fn set_auction_extension(
+        context: Context<SetAuctionExtensionAccountConstraints>,
+        extension_window: i64,
+        extension_duration: i64,
+        max_extension: i64,
+    ) -> Result<()> {
+        handlers::ticketfair_event::set_auction_extension(
+            context,
+            extension_window,
+            extension_duration,
+            max_extension
+        )
+    }
+
+    /// Choose whether a Ticketfair auction settles bids at the clearing price via the settle_bid crank.
+    pub fn set_uniform_price(
+        context: Context<SetUniformPriceAccountConstraints>,
+        enabled: bool,
+    ) -> Result<()> {
+        handlers::ticketfair_event::set_uniform_price(context, enabled)
+    }
+
+    /// Choose whether a Ticketfair auction awards tickets in bid order via the award_next_ticket crank.
+    pub fn set_sequential_awards(
+        context: Context<SetSequentialAwardsAccountConstraints>,
+        enabled: bool,
+    ) -> Result<()> {
+        handlers::ticketfair_event::set_sequential_awards(context, enabled)
+    }
+
+    /// Choose whether Ticketfair bids are awarded and ticketed as they're placed, like a classic Dutch auction.
+    pub fn set_instant_buy(
+        context: Context<SetInstantBuyAccountConstraints>,
+        enabled: bool,
+    ) -> Result<()> {
+        handlers::ticketfair_event::set_instant_buy(context, enabled)
+    }
+
+    /// Set when withdrawing a Ticketfair bid starts costing a fee, and how much of the bid the fee is.
+    pub fn set_withdrawal_policy(
+        context: Context<SetWithdrawalPolicyAccountConstraints>,
+        cutoff: i64,
+        fee_bps: u16,
+    ) -> Result<()> {
+        handlers::ticketfair_event::set_withdrawal_policy(context, cutoff, fee_bps)
+    }
+
+    /// Set whether Ticketfair tickets can be transferred, the resale price cap and the organizer's resale royalty.
+    pub fn set_transfer_policy(
+        context: Context<SetTransferPolicyAccountConstraints>,
+        policy: u8,
+        resale_cap_bps: u16,
+        royalty_bps: u16,
+    ) -> Result<()> {
+        handlers::ticketfair_event::set_transfer_policy(context, policy, resale_cap_bps, royalty_bps)
+    }
+
+    /// Edit a Ticketfair event's metadata, base tier supply and prices, and auction times before activation.
+    pub fn update_event(
+        context: Context<UpdateEventAccountConstraints>,
+        metadata_url: String,
+        ticket_supply: u32,
+        start_price: u64,
+        end_price: u64,
+        auction_start_time: i64,
+        auction_end_time: i64,
+    ) -> Result<()> {
+        handlers::ticketfair_event::update_event(
+            context,
+            metadata_url,
+            ticket_supply,
+            start_price,
+            end_price,
+            auction_start_time,
+            auction_end_time
+        )
+    }
+
+    /// Add a ticket tier (e.g. VIP) with its own supply and price curve to a Ticketfair event.
+    pub fn add_ticket_tier(
+        context: Context<AddTicketTierAccountConstraints>,
+        supply: u32,
+        start_price: u64,
+        end_price: u64,
+    ) -> Result<()> {
+        handlers::ticketfair_event::add_ticket_tier(context, supply, start_price, end_price)
+    }
+
+    /// Withdraw the proceeds of a finalized Ticketfair auction to the organizer.
+    pub fn withdraw_proceeds(
+        context: Context<WithdrawProceedsAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_event::withdraw_proceeds(context)
+    }
+
+    /// Set how a Ticketfair event's proceeds are split between payees before activation.
+    pub fn set_payees(
+        context: Context<SetPayeesAccountConstraints>,
+        payees: Vec<Payee>,
+        dust_payee: u8,
     ) -> Result<()> {
-        handlers::ticketfair_event::finalize_auction(context, close_price)
+        handlers::ticketfair_event::set_payees(context, payees, dust_payee)
     }
 
-    /// Place a bid for a ticket in a Ticketfair Dutch auction.
+    /// Distribute the proceeds of a finalized Ticketfair auction to all of its payees.
+    pub fn distribute_proceeds<'info>(
+        context: Context<'_, '_, 'info, 'info, DistributeProceedsAccountConstraints<'info>>,
+    ) -> Result<()> {
+        handlers::ticketfair_event::distribute_proceeds(context)
+    }
+
+    /// Close a fully settled Ticketfair event, returning its rent and any escrow leftovers to the organizer.
+    pub fn close_event(
+        context: Context<CloseEventAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_event::close_event(context)
+    }
+
+    /// Create a page of cNFT asset ID inventory for a Ticketfair event.
+    pub fn create_ticket_inventory_page(
+        context: Context<CreateTicketInventoryPageAccountConstraints>,
+        page_index: u32,
+    ) -> Result<()> {
+        handlers::ticketfair_inventory::create_ticket_inventory_page(context, page_index)
+    }
+
+    /// Register a chunk of minted cNFT asset IDs in a Ticketfair event's inventory.
+    pub fn register_ticket_assets(
+        context: Context<RegisterTicketAssetsAccountConstraints>,
+        start_index: u32,
+        asset_ids: Vec<Pubkey>,
+    ) -> Result<()> {
+        handlers::ticketfair_inventory::register_ticket_assets(context, start_index, asset_ids)
+    }
+
+    /// Place a bid for one or more tickets in a Ticketfair Dutch auction, at the current price up to a maximum.
     pub fn place_bid(
         context: Context<PlaceBidAccountConstraints>,
-        amount: u64,
+        max_price: u64,
+        quantity: u8,
+        tier: u8,
+    ) -> Result<()> {
+        handlers::ticketfair_bid::place_bid(context, max_price, quantity, tier)
+    }
+
+    /// Place a standing limit bid below the current price of a Ticketfair Dutch auction.
+    pub fn place_limit_bid(
+        context: Context<PlaceLimitBidAccountConstraints>,
+        limit_price: u64,
+        quantity: u8,
+        tier: u8,
+    ) -> Result<()> {
+        handlers::ticketfair_limit_bid::place_limit_bid(context, limit_price, quantity, tier)
+    }
+
+    /// Turn a limit bid into a real bid once the price has fallen to its limit (callable by anyone).
+    pub fn execute_limit_bid(
+        context: Context<ExecuteLimitBidAccountConstraints>,
     ) -> Result<()> {
-        handlers::ticketfair_bid::place_bid(context, amount)
+        handlers::ticketfair_limit_bid::execute_limit_bid(context)
     }
 
-    /// Award a ticket to a bid in a Ticketfair Dutch auction.
+    /// Award some or all of a bid's tickets in a Ticketfair Dutch auction.
     pub fn award_ticket(
         context: Context<AwardTicketAccountConstraints>,
-        cnft_asset_id: Pubkey,
+        quantity: u8,
     ) -> Result<()> {
-        handlers::ticketfair_bid::award_ticket(context, cnft_asset_id)
+        handlers::ticketfair_bid::award_ticket(context, quantity)
+    }
+
+    /// Award tickets to many Ticketfair bids at once, returning the bids that were skipped.
+    pub fn award_tickets_batch<'info>(
+        context: Context<'_, '_, 'info, 'info, AwardTicketsBatchAccountConstraints<'info>>,
+    ) -> Result<Vec<SkippedBid>> {
+        handlers::ticketfair_bid::award_tickets_batch(context)
+    }
+
+    /// Award the next bid in sequence in a first-come Ticketfair auction (callable by anyone).
+    pub fn award_next_ticket(
+        context: Context<AwardNextTicketAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_bid::award_next_ticket(context)
+    }
+
+    /// Skip the next bid in sequence when it can never be awarded (callable by anyone).
+    pub fn skip_bid(
+        context: Context<SkipBidAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_bid::skip_bid(context)
     }
 
     /// Refund a bid in a Ticketfair Dutch auction.
@@ -108,4 +340,86 @@ pub mod escrow {
     ) -> Result<()> {
         handlers::ticketfair_bid::refund_bid(context)
     }
+
+    /// Withdraw a Ticketfair bid while the auction is running, under the event's withdrawal policy.
+    pub fn withdraw_bid(
+        context: Context<WithdrawBidAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_bid::withdraw_bid(context)
+    }
+
+    /// Pay a bid back down to the clearing price of a uniform-price Ticketfair auction (callable by anyone).
+    pub fn settle_bid(
+        context: Context<SettleBidAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_bid::settle_bid(context)
+    }
+
+    /// Turn a Ticketfair event into a sealed-bid commit-reveal auction before activation.
+    pub fn configure_sealed_bid(
+        context: Context<ConfigureSealedBidAccountConstraints>,
+        reveal_end_time: i64,
+        deposit: u64,
+        unrevealed_slash_bps: u16,
+    ) -> Result<()> {
+        handlers::ticketfair_sealed_bid::configure_sealed_bid(
+            context,
+            reveal_end_time,
+            deposit,
+            unrevealed_slash_bps
+        )
+    }
+
+    /// Commit a sealed bid with a deposit during a Ticketfair sealed-bid auction's commit window.
+    pub fn commit_bid(
+        context: Context<CommitBidAccountConstraints>,
+        commitment: [u8; 32],
+        quantity: u8,
+    ) -> Result<()> {
+        handlers::ticketfair_sealed_bid::commit_bid(context, commitment, quantity)
+    }
+
+    /// Reveal a sealed bid during a Ticketfair sealed-bid auction's reveal window.
+    pub fn reveal_bid(
+        context: Context<RevealBidAccountConstraints>,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        handlers::ticketfair_sealed_bid::reveal_bid(context, amount, salt)
+    }
+
+    /// Rank the revealed bids of a Ticketfair sealed-bid auction, marking the winners.
+    pub fn rank_sealed_bids<'info>(
+        context: Context<'_, '_, 'info, 'info, RankSealedBidsAccountConstraints<'info>>,
+    ) -> Result<()> {
+        handlers::ticketfair_sealed_bid::rank_sealed_bids(context)
+    }
+
+    /// Commit to the randomness that will draw winners for an oversubscribed Ticketfair auction.
+    pub fn commit_lottery(
+        context: Context<CommitLotteryAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_lottery::commit_lottery(context)
+    }
+
+    /// Draw lottery winners among the bids at a Ticketfair auction's end price.
+    pub fn draw_winners<'info>(
+        context: Context<'_, '_, 'info, 'info, DrawWinnersAccountConstraints<'info>>,
+    ) -> Result<()> {
+        handlers::ticketfair_lottery::draw_winners(context)
+    }
+
+    /// Return or revoke a Ticketfair ticket, reissuing it to the next bid on its tier's waitlist or returning it to the tier.
+    pub fn release_ticket(
+        context: Context<ReleaseTicketAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_waitlist::release_ticket(context)
+    }
+
+    /// Close a settled Ticketfair bid, returning its rent to the bidder.
+    pub fn close_bid(
+        context: Context<CloseBidAccountConstraints>,
+    ) -> Result<()> {
+        handlers::ticketfair_bid::close_bid(context)
+    }
 }
\ No newline at end of file
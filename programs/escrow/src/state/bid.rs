@@ -1,24 +1,246 @@
 //! Ticketfair Bid account definition (Dutch Auction)
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::state::Event;
+
+/// A bid award_tickets_batch passed over, returned to the caller as return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SkippedBid {
+    /// Address of the bid that wasn't awarded
+    pub bid: Pubkey,
+    /// Program error code explaining why (the same error award_ticket would have failed with)
+    pub error_code: u32,
+}
 
 #[account]
+#[derive(Default)]
 pub struct Bid {
     pub bidder: Pubkey,
     pub event: Pubkey,
-    pub amount: u64,
+    pub amount: u64, // Price per ticket
     pub status: u8, // Use constants: BID_STATUS_PENDING, BID_STATUS_AWARDED, BID_STATUS_REFUNDED
     pub bump: u8,
+    /// Number of tickets bid for (1 to MAX_TICKETS_PER_BID)
+    pub quantity: u8,
+    /// Number of tickets actually awarded (may be less than quantity)
+    pub quantity_awarded: u8,
+    /// Index of the event ticket tier this bid is for
+    pub tier: u8,
+    /// Whether everything owed back to the bidder has been paid out
+    pub settled: bool,
+    /// Sealed-bid auctions: hash of (amount, salt) submitted while bids are sealed
+    pub commitment: [u8; 32],
+    /// Whether this bid was drawn as a winner in the event's lottery
+    pub lottery_winner: bool,
+    /// Order the bid was placed in within its event (0 for the first bid)
+    pub sequence: u64,
+    /// Slot the bid was placed in
+    pub slot: u64,
+    /// Position in its tier's waitlist (only meaningful while the bid is waitlisted)
+    pub waitlist_position: u32,
+    /// Sealed-bid auctions: whether rank_sealed_bids placed this bid among the winners
+    pub sealed_winner: bool,
 }
 
 impl Bid {
-    pub const INIT_SPACE: usize = 32 + 32 + 8 + 1 + 1;
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 1 + 1 + 1 + 1 + 1 + 1 + 32 + 1 + 8 + 8 + 4 + 1;
+
+    /// Commitment to a sealed bid of `amount` per ticket, as submitted by commit_bid
+    pub fn sealed_commitment(amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&amount.to_le_bytes(), salt]).to_bytes()
+    }
+
+    /// Enter the bid into the auction in `slot`: it takes the next sequence number, and joins the back
+    /// of its tier's waitlist if the tier has sold out (instant-buy events reject those bids instead).
+    /// Tier 0 bids are counted as lottery pool entries, or as outbidding the pool.
+    pub fn open(&mut self, event: &mut Event, slot: u64) -> Result<()> {
+        let waitlisted = !event.instant_buy && event.is_sold_out(self.tier);
+        self.status = if waitlisted { BID_STATUS_WAITLISTED } else { BID_STATUS_PENDING };
+        self.sequence = event.next_bid_sequence()?;
+        self.slot = slot;
+        if waitlisted {
+            let ticket_tier = event
+                .tiers
+                .get_mut(self.tier as usize)
+                .ok_or(error!(ErrorCode::InvalidTicketTier))?;
+            self.waitlist_position = ticket_tier.waitlist_tail;
+            ticket_tier.waitlist_tail = ticket_tier.waitlist_tail.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+        } else if event.is_lottery_pool_bid(self.tier, self.amount) {
+            event.lottery_pool_bids = event.lottery_pool_bids.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+        } else if event.is_above_lottery_pool(self.tier, self.amount) {
+            event.tickets_bid_above_pool = event
+                .tickets_bid_above_pool
+                .checked_add(self.quantity as u32)
+                .ok_or(error!(ErrorCode::CustomError))?;
+        }
+        Ok(())
+    }
+
+    /// Undo open()'s counts once a pending bid stops competing for tickets, because it's been awarded
+    /// or withdrawn: it leaves the lottery pool, or stops holding tickets back from the draw
+    pub fn leave_auction(&self, event: &mut Event) {
+        if event.is_lottery_pool_bid(self.tier, self.amount) {
+            event.lottery_pool_bids = event.lottery_pool_bids.saturating_sub(1);
+        } else if event.is_above_lottery_pool(self.tier, self.amount) {
+            event.tickets_bid_above_pool = event.tickets_bid_above_pool.saturating_sub(self.quantity as u32);
+        }
+    }
+
+    /// Total amount escrowed for this bid (price per ticket * quantity)
+    pub fn total_amount(&self) -> Option<u64> {
+        self.amount.checked_mul(self.quantity as u64)
+    }
+
+    /// Amount owed back to an awarded bidder once the clearing price is known:
+    /// unawarded tickets are refunded in full, awarded tickets down to the clearing price
+    pub fn refund_due(&self, close_price: u64) -> Option<u64> {
+        let unawarded = self.quantity.checked_sub(self.quantity_awarded)? as u64;
+        let overpaid = self.amount.saturating_sub(close_price);
+        unawarded
+            .checked_mul(self.amount)?
+            .checked_add(overpaid.checked_mul(self.quantity_awarded as u64)?)
+    }
 
     /// Check if the bid can be refunded
     pub fn can_refund(&self) -> bool {
-        // Only allow refund if not already refunded
-        self.status != BID_STATUS_REFUNDED
+        // Only allow refund if not already refunded or settled
+        self.status != BID_STATUS_REFUNDED && !self.settled
+    }
+
+    /// Work out what is owed back to the bidder and mark the bid settled, so it is only paid once.
+    /// Losing bids and bids in failed events get everything back; winning bids get back the
+    /// difference from their tier's clearing price plus the price of any tickets not awarded.
+    pub fn settle(&mut self, event: &Event) -> Result<u64> {
+        if !self.can_refund() {
+            return Err(error!(ErrorCode::CustomError)); // Already refunded
+        }
+
+        let refund_amount;
+        if event.status == EVENT_STATUS_FAILED {
+            // Event failed to reach minimum sales: every bid, awarded or not, gets a full refund
+            refund_amount = self.total_amount().ok_or(error!(ErrorCode::CustomError))?;
+            self.status = BID_STATUS_REFUNDED;
+        } else if (self.status == BID_STATUS_PENDING || self.status == BID_STATUS_LIMIT) &&
+            (event.status == EVENT_STATUS_ACTIVE || event.status == EVENT_STATUS_CREATED)
+        {
+            // Bids may still win until the auction is over; taking one back early goes through withdraw_bid
+            return Err(error!(ErrorCode::AuctionNotFinalized));
+        } else if self.status == BID_STATUS_PENDING {
+            // Case 1: Bid did not win, full refund
+            refund_amount = self.total_amount().ok_or(error!(ErrorCode::CustomError))?;
+            self.status = BID_STATUS_REFUNDED;
+        } else if self.status == BID_STATUS_AWARDED {
+            // Case 2: Bid won, partial refund per ticket if closing price < bid amount,
+            // plus a full refund for any tickets in the bid that were not awarded
+            // We need the auction to be finalized to know the closing price
+            if event.status != EVENT_STATUS_FINALIZED || event.auction_close_price == 0 {
+                return Err(error!(ErrorCode::CustomError)); // Auction not finalized, can't refund
+            }
+
+            let close_price = event
+                .tier_close_price(self.tier)
+                .ok_or(error!(ErrorCode::InvalidTicketTier))?;
+            refund_amount = self
+                .refund_due(close_price)
+                .ok_or(error!(ErrorCode::CustomError))?;
+            // Do not mark as refunded, as the ticket is already awarded
+        } else if self.status == BID_STATUS_COMMITTED {
+            // Sealed bid that was never revealed: the deposit comes back less the slash, which stays
+            // in the escrow and is paid out with the proceeds
+            if event.status != EVENT_STATUS_FINALIZED {
+                return Err(error!(ErrorCode::AuctionNotFinalized));
+            }
+            let deposit = self.total_amount().ok_or(error!(ErrorCode::CustomError))?;
+            refund_amount = deposit - event.unrevealed_slash(deposit);
+            self.status = BID_STATUS_REFUNDED;
+        } else if self.status == BID_STATUS_LIMIT {
+            // Limit bid that was never executed, full refund
+            refund_amount = self.total_amount().ok_or(error!(ErrorCode::CustomError))?;
+            self.status = BID_STATUS_REFUNDED;
+        } else if self.status == BID_STATUS_WAITLISTED {
+            // Waitlisted bid that was never promoted: it can still be promoted until bidding is over
+            if event.status == EVENT_STATUS_ACTIVE || event.status == EVENT_STATUS_CREATED {
+                return Err(error!(ErrorCode::AuctionNotFinalized));
+            }
+            refund_amount = self.total_amount().ok_or(error!(ErrorCode::CustomError))?;
+            self.status = BID_STATUS_REFUNDED;
+        } else {
+            return Err(error!(ErrorCode::CustomError)); // Invalid bid status
+        }
+
+        self.settled = true;
+        Ok(refund_amount)
+    }
+
+    /// Withdraw a pending or limit bid while a Dutch auction is running, marking it settled and taking a
+    /// pending bid out of the event's counts. Returns the amount owed back to the bidder and the
+    /// withdrawal fee owed to the organizer.
+    pub fn withdraw(&mut self, event: &mut Event, now: i64) -> Result<(u64, u64)> {
+        if (self.status != BID_STATUS_PENDING && self.status != BID_STATUS_LIMIT) || event.status != EVENT_STATUS_ACTIVE {
+            return Err(error!(ErrorCode::BidNotWithdrawable));
+        }
+        // Sealed bids stay in through the commit, reveal and ranking phases: revealed bids are counted
+        // for rank_sealed_bids, which has to be passed every one of them, and ranking them starts
+        // award processing, which closes withdrawals anyway
+        if event.is_sealed_bid() {
+            return Err(error!(ErrorCode::BidNotWithdrawable));
+        }
+        let total = self.total_amount().ok_or(error!(ErrorCode::CustomError))?;
+        let fee = event.withdrawal_fee(total, now)?;
+        if self.status == BID_STATUS_PENDING {
+            self.leave_auction(event);
+        }
+        self.status = BID_STATUS_REFUNDED;
+        self.settled = true;
+        Ok((total - fee, fee))
+    }
+
+    /// Execute a limit bid at `price`, its tier's current price: it's re-priced to match a bid placed
+    /// directly at that price, and whatever was escrowed above it is owed back to the bidder
+    pub fn execute_limit(&mut self, price: u64) -> Result<u64> {
+        if self.status != BID_STATUS_LIMIT {
+            return Err(error!(ErrorCode::NotLimitBid));
+        }
+        let excess = self
+            .amount
+            .checked_sub(price)
+            .and_then(|difference| difference.checked_mul(self.quantity as u64))
+            .ok_or(error!(ErrorCode::CurrentPriceAboveMax))?;
+        self.amount = price;
+        Ok(excess)
+    }
+
+    /// Check if the bid still holds a place in a sequential-award queue, so it has to stay
+    /// around for skip_bid until the queue has moved past it
+    pub fn holds_queue_position(&self, event: &Event) -> bool {
+        event.sequential_awards &&
+        event.status == EVENT_STATUS_ACTIVE &&
+        self.slot != 0 && // Limit bids that were never executed never joined the queue
+        self.sequence >= event.next_award_sequence
+    }
+
+    /// Check if the bid account can be closed (nothing is still owed to the bidder)
+    pub fn can_close(&self) -> bool {
+        self.settled
+    }
+
+    /// Take back `quantity` awarded tickets that have been released to the waitlist, returning what
+    /// the bidder paid for them. A bid left with no tickets at all is settled.
+    pub fn release_tickets(&mut self, quantity: u8) -> Result<u64> {
+        if self.status != BID_STATUS_AWARDED || quantity > self.quantity_awarded {
+            return Err(error!(ErrorCode::CustomError)); // Replace with TicketNotAwarded if desired
+        }
+        let refund_amount = self.amount.checked_mul(quantity as u64).ok_or(error!(ErrorCode::CustomError))?;
+        self.quantity -= quantity;
+        self.quantity_awarded -= quantity;
+        if self.quantity == 0 {
+            self.status = BID_STATUS_REFUNDED;
+            self.settled = true;
+        }
+        Ok(refund_amount)
     }
 
     /// Check if the bid can be awarded a ticket
@@ -26,4 +248,24 @@ impl Bid {
         // Only pending bids can be awarded
         self.status == BID_STATUS_PENDING
     }
-}
\ No newline at end of file
+}
+
+/// Pick the winners of a sealed-bid auction from its revealed bids, given as (amount, sequence,
+/// quantity). Bids are taken highest first, earliest first on ties, until one no longer fits in
+/// the tickets available; no lower bid is taken past it, so a winner never outranks a loser.
+pub fn sealed_bid_winners(entries: &[(u64, u64, u8)], tickets_available: u32) -> Vec<bool> {
+    let mut ranked: Vec<usize> = (0..entries.len()).collect();
+    ranked.sort_by_key(|&index| (std::cmp::Reverse(entries[index].0), entries[index].1));
+
+    let mut winners = vec![false; entries.len()];
+    let mut remaining = tickets_available;
+    for index in ranked {
+        let quantity = entries[index].2 as u32;
+        if quantity > remaining {
+            break;
+        }
+        winners[index] = true;
+        remaining -= quantity;
+    }
+    winners
+}
@@ -2,35 +2,199 @@
 
 use anchor_lang::prelude::*;
 use crate::constants::*;
+use crate::error::ErrorCode;
+
+/// A class of tickets within an event (e.g. GA, VIP, balcony) with its own supply and price curve
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct TicketTier {
+    /// Number of tickets available in this tier
+    pub supply: u32,
+    /// Number of tickets awarded in this tier so far
+    pub awarded: u32,
+    /// Starting price for this tier's Dutch auction
+    pub start_price: u64,
+    /// Ending price for this tier's Dutch auction
+    pub end_price: u64,
+    /// The price at which this tier closed (0 if not finalized)
+    pub close_price: u64,
+    /// Waitlist position of the next waitlisted bid to be promoted
+    pub waitlist_head: u32,
+    /// Waitlist position the next bid placed after sellout will take
+    pub waitlist_tail: u32,
+    /// Lowest price per ticket paid by a bid awarded in this tier (0 until the first award).
+    /// The tier can't close above it, or winners would owe more than they escrowed.
+    pub lowest_awarded_bid: u64,
+}
+
+impl TicketTier {
+    pub const INIT_SPACE: usize = 4 + 4 + 8 + 8 + 8 + 4 + 4 + 8;
+
+    /// Check if every ticket in this tier has been awarded
+    pub fn is_sold_out(&self) -> bool {
+        self.awarded >= self.supply
+    }
+
+    /// Check if any waitlisted bids are still waiting for a ticket
+    pub fn has_waitlist(&self) -> bool {
+        self.waitlist_head < self.waitlist_tail
+    }
+
+    /// Record the price per ticket paid by a bid awarded tickets in this tier
+    pub fn record_award_price(&mut self, amount: u64) {
+        if self.lowest_awarded_bid == 0 || amount < self.lowest_awarded_bid {
+            self.lowest_awarded_bid = amount;
+        }
+    }
+
+    /// Check every bid awarded in this tier paid at least `close_price` per ticket
+    pub fn clears_awarded_bids(&self, close_price: u64) -> bool {
+        self.awarded == 0 || close_price <= self.lowest_awarded_bid
+    }
+
+    /// Price per ticket this tier's proceeds are paid at: its close price, but never more than
+    /// the lowest awarded bid paid, so the organizer can't be paid more than winners escrowed
+    pub fn proceeds_price(&self) -> u64 {
+        if self.lowest_awarded_bid == 0 {
+            self.close_price
+        } else {
+            self.close_price.min(self.lowest_awarded_bid)
+        }
+    }
+}
+
+/// A recipient of a share of the event's proceeds (e.g. promoter, venue, artist)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct Payee {
+    /// Wallet that receives this share
+    pub recipient: Pubkey,
+    /// Share of the proceeds in basis points (10,000 = 100%)
+    pub share_bps: u16,
+}
+
+impl Payee {
+    pub const INIT_SPACE: usize = 32 + 2;
+}
+
+/// Calculate a Dutch auction price that falls linearly from start_price to end_price over the auction window.
+pub fn dutch_auction_price(start_price: u64, end_price: u64, start_time: i64, end_time: i64, now: i64) -> u64 {
+    if now <= start_time {
+        start_price
+    } else if now >= end_time {
+        end_price
+    } else {
+        let elapsed = now - start_time;
+        let duration = end_time - start_time;
+        let price_diff = start_price.saturating_sub(end_price);
+        start_price - ((price_diff as i64 * elapsed) / duration) as u64
+    }
+}
 
 #[account]
+#[derive(Default)]
 pub struct Event {
     /// The event organizer
     pub organizer: Pubkey,
     /// Off-chain metadata reference (e.g., Walrus blob URL)
     pub metadata_url: String,
-    /// Total number of tickets available
+    /// Total number of tickets available across all tiers
     pub ticket_supply: u32,
-    /// Number of tickets awarded so far
+    /// Number of tickets awarded so far across all tiers
     pub tickets_awarded: u32,
-    /// Starting price for Dutch auction (in lamports)
+    /// Number of bid accounts that have been placed and not yet closed
+    pub bids_open: u32,
+    /// Minimum number of tickets that must be awarded for the event to go ahead (0 = no minimum)
+    pub min_tickets_sold: u32,
+    /// Maximum number of tickets a single wallet may hold (0 = no cap)
+    pub max_tickets_per_wallet: u32,
+    /// Whether the event becomes active on its own once auction_start_time is reached
+    pub auto_activate: bool,
+    /// Starting price for Dutch auction of tier 0 (in lamports, or base units of the payment mint)
     pub start_price: u64,
-    /// Ending price for Dutch auction (in lamports)
+    /// Ending price for Dutch auction of tier 0 (in lamports, or base units of the payment mint)
     pub end_price: u64,
     /// Auction start time (Unix timestamp)
     pub auction_start_time: i64,
-    /// Auction end time (Unix timestamp)
+    /// Auction end time as originally scheduled (Unix timestamp)
     pub auction_end_time: i64,
-    /// The price at which the auction closed (set when auction ends, 0 if not finalized)
+    /// Auction end time after anti-sniping extensions (Unix timestamp)
+    pub extended_end_time: i64,
+    /// Bids landing within this many seconds of the end extend the auction (0 = no extensions)
+    pub extension_window: i64,
+    /// Seconds added to the end time by each extension
+    pub extension_duration: i64,
+    /// Maximum total seconds the auction can be extended past its original end time
+    pub max_extension: i64,
+    /// The price at which tier 0 of the auction closed (set when auction ends, 0 if not finalized)
     pub auction_close_price: u64,
-    /// Current status (0 = Created, 1 = Active, 2 = Finalized, 3 = Cancelled)
+    /// SPL token mint bids are paid in (Pubkey::default() for native SOL)
+    pub payment_mint: Pubkey,
+    /// Auction type (0 = Dutch, 1 = Sealed-bid commit-reveal)
+    pub auction_type: u8,
+    /// Sealed-bid auctions: end of the reveal window that follows the commit window (Unix timestamp)
+    pub reveal_end_time: i64,
+    /// Sealed-bid auctions: deposit escrowed per ticket when committing, also the reserve price
+    pub sealed_deposit: u64,
+    /// Sealed-bid auctions: share of an unrevealed bid's deposit kept by the organizer (10,000 = all of it)
+    pub unrevealed_slash_bps: u16,
+    /// Sealed-bid auctions: lowest amount among awarded bids (the highest possible clearing price)
+    pub lowest_winning_bid: u64,
+    /// Whether bids are settled at the clearing price by a permissionless settle_bid crank
+    pub uniform_price: bool,
+    /// Whether the organizer has withdrawn the auction proceeds
+    pub proceeds_withdrawn: bool,
+    /// Proceeds split, paid out by distribute_proceeds (empty = everything goes to the organizer)
+    pub payees: Vec<Payee>, // #[max_len = MAX_PAYEES]
+    /// Index of the payee who receives rounding dust from the split
+    pub dust_payee: u8,
+    /// Ticket tiers, each with its own supply and price curve (tier 0 mirrors the event-level prices)
+    pub tiers: Vec<TicketTier>, // #[max_len = MAX_TICKET_TIERS]
+    /// Current status (0 = Created, 1 = Active, 2 = Finalized, 3 = Cancelled, 4 = Failed)
     pub status: u8,
     /// PDA bump
     pub bump: u8,
     /// Bubblegum Merkle Tree address for cNFTs
     pub merkle_tree: Pubkey,
-    /// Asset IDs of cNFTs minted for this event (max 1000 tickets)
-    pub cnft_asset_ids: Vec<Pubkey>, // #[max_len = 1000]
+    /// Number of cNFT asset IDs registered in the event's TicketInventoryPage accounts
+    pub assets_registered: u32,
+    /// Number of bids placed at tier 0's end price, which a lottery draws winners from
+    pub lottery_pool_bids: u32,
+    /// Randomness account committed for the lottery (Pubkey::default() if no lottery)
+    pub lottery_randomness: Pubkey,
+    /// Whether lottery winners have been drawn
+    pub lottery_drawn: bool,
+    /// Number of bids ever placed, used as the sequence number of the next bid
+    pub bids_placed: u64,
+    /// Whether tickets are awarded first-come in bid sequence order by the permissionless award_next_ticket
+    pub sequential_awards: bool,
+    /// Sequential awards: sequence number of the next bid to be awarded or skipped
+    pub next_award_sequence: u64,
+    /// Whether place_bid awards the bid and issues its ticket straight away, like a classic Dutch auction
+    pub instant_buy: bool,
+    /// Bids withdrawn from this time on pay the withdrawal fee (Unix timestamp, 0 = withdrawals are always free)
+    pub withdrawal_cutoff: i64,
+    /// Share of a withdrawn bid paid to the organizer after the cutoff, in basis points
+    pub withdrawal_fee_bps: u16,
+    /// Whether award processing has started (the first award or lottery commitment), after which bids can't be withdrawn
+    pub award_processing_started: bool,
+    /// Whether and how tickets can change hands (see TRANSFER_POLICY_* constants)
+    pub transfer_policy: u8,
+    /// Capped transfers: maximum resale price per admission, in basis points of the ticket tier's close price
+    pub resale_cap_bps: u16,
+    /// Share of every resale price paid to the organizer, in basis points
+    pub royalty_bps: u16,
+    /// Sealed bids: total kept from committed deposits if their bids are never revealed, paid out with the proceeds
+    pub unrevealed_slashes: u64,
+    /// Sealed bids: number of bids revealed, every one of which rank_sealed_bids has to be passed
+    pub sealed_bids_revealed: u32,
+    /// Sealed bids: whether the revealed bids have been ranked, after which only the winners can be awarded
+    pub sealed_bids_ranked: bool,
+    /// Tickets wanted by pending tier 0 bids above the lottery pool's price, which the draw holds back for them
+    pub tickets_bid_above_pool: u32,
+    /// Number of the event's tickets listed for resale, whose listings need the event to be cancelled or bought
+    pub listings_open: u32,
+    /// Position in the inventory of the next asset to award. Unlike tickets_awarded it never goes back down,
+    /// as tickets returned to their tier don't put their assets back in the inventory.
+    pub assets_awarded: u32,
 }
 
 impl Event {
@@ -38,27 +202,228 @@ impl Event {
                                4 + MAX_METADATA_URL_LEN + // metadata_url string
                                4 + // ticket_supply
                                4 + // tickets_awarded
+                               4 + // bids_open
+                               4 + // min_tickets_sold
+                               4 + // max_tickets_per_wallet
+                               1 + // auto_activate
                                8 + // start_price
                                8 + // end_price
                                8 + // auction_start_time
                                8 + // auction_end_time
+                               8 + // extended_end_time
+                               8 + // extension_window
+                               8 + // extension_duration
+                               8 + // max_extension
                                8 + // auction_close_price
+                               32 + // payment_mint
+                               1 + // auction_type
+                               8 + // reveal_end_time
+                               8 + // sealed_deposit
+                               2 + // unrevealed_slash_bps
+                               8 + // lowest_winning_bid
+                               1 + // uniform_price
+                               1 + // proceeds_withdrawn
+                               4 + (Payee::INIT_SPACE * MAX_PAYEES) + // payees vector
+                               1 + // dust_payee
+                               4 + (TicketTier::INIT_SPACE * MAX_TICKET_TIERS) + // tiers vector
                                1 + // status 
                                1 + // bump
                                32 + // merkle_tree
-                               4 + (32 * MAX_TICKETS_TEST_MODE as usize); // cnft_asset_ids vector
+                               4 + // assets_registered
+                               4 + // lottery_pool_bids
+                               32 + // lottery_randomness
+                               1 + // lottery_drawn
+                               8 + // bids_placed
+                               1 + // sequential_awards
+                               8 + // next_award_sequence
+                               1 + // instant_buy
+                               8 + // withdrawal_cutoff
+                               2 + // withdrawal_fee_bps
+                               1 + // award_processing_started
+                               1 + // transfer_policy
+                               2 + // resale_cap_bps
+                               2 + // royalty_bps
+                               8 + // unrevealed_slashes
+                               4 + // sealed_bids_revealed
+                               1 + // sealed_bids_ranked
+                               4 + // tickets_bid_above_pool
+                               4 + // listings_open
+                               4; // assets_awarded
+
+    /// Total ticket supply if the base tier (tier 0) had the given supply
+    pub fn supply_with_base_tier(&self, base_supply: u32) -> u32 {
+        let base_tier_supply = self.tiers.first().map_or(0, |ticket_tier| ticket_tier.supply);
+        self.ticket_supply
+            .saturating_sub(base_tier_supply)
+            .saturating_add(base_supply)
+    }
+
+    /// Lowest allowed ticket price: the lamport minimum for SOL events, any non-zero amount for token events
+    pub fn min_ticket_price(&self) -> u64 {
+        if self.is_token_denominated() { 1 } else { MIN_TICKET_PRICE }
+    }
+
+    /// Validate the event's configurable parameters (shared by create_event, add_ticket_tier and update_event)
+    pub fn validate_params(&self) -> Result<()> {
+        if self.metadata_url.len() > MAX_METADATA_URL_LEN {
+            return Err(error!(ErrorCode::MetadataUrlTooLong));
+        }
+        
+        if self.ticket_supply > MAX_TICKETS_PER_EVENT {
+            return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+        }
+        
+        if self.min_tickets_sold > self.ticket_supply {
+            return Err(error!(ErrorCode::MinTicketsExceedsSupply));
+        }
+        
+        // Supply can't be cut below the tickets already backed by registered assets or already awarded
+        if self.asset_capacity() < self.assets_registered ||
+            self.ticket_supply < self.tickets_awarded ||
+            self.tiers.iter().any(|ticket_tier| ticket_tier.supply < ticket_tier.awarded)
+        {
+            return Err(error!(ErrorCode::SupplyBelowIssuedTickets));
+        }
+        
+        // Prices are in the payment mint's base units for token events, so the lamport minimum only applies to SOL
+        let min_price = self.min_ticket_price();
+        if self.start_price < min_price || self.end_price < min_price {
+            return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+        }
+        for ticket_tier in &self.tiers {
+            if ticket_tier.start_price < min_price || ticket_tier.end_price < min_price {
+                return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+            }
+        }
+        
+        let duration = self.auction_end_time - self.auction_start_time;
+        if !(MIN_AUCTION_DURATION..=MAX_AUCTION_DURATION).contains(&duration) {
+            return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+        }
+        
+        // Sealed bids all clear at one price, so they need a single tier and a reveal window after commits close
+        if self.is_sealed_bid() && (
+            self.tiers.len() > 1 ||
+            self.reveal_end_time <= self.auction_end_time ||
+            self.sealed_deposit < min_price ||
+            self.unrevealed_slash_bps > TOTAL_BASIS_POINTS ||
+            self.sequential_awards ||
+            self.instant_buy
+        ) {
+            return Err(error!(ErrorCode::InvalidSealedBidConfig));
+        }
+        
+        // Instant-buy bids are awarded as they're placed, so there is no queue left to award in sequence
+        if self.instant_buy && self.sequential_awards {
+            return Err(error!(ErrorCode::ConflictingAwardModes));
+        }
+        
+        self.validate_transfer_policy()
+    }
+
+    /// Check the transfer policy is a known one, capped transfers have a cap above zero and within
+    /// MAX_RESALE_CAP_BPS (other policies have none), and the royalty is at most the whole price
+    pub fn validate_transfer_policy(&self) -> Result<()> {
+        let cap_valid = match self.transfer_policy {
+            TRANSFER_POLICY_NONE | TRANSFER_POLICY_FREE => self.resale_cap_bps == 0,
+            TRANSFER_POLICY_CAPPED => (1..=MAX_RESALE_CAP_BPS).contains(&self.resale_cap_bps),
+            _ => false,
+        };
+        if !cap_valid || self.royalty_bps > TOTAL_BASIS_POINTS {
+            return Err(error!(ErrorCode::InvalidTransferPolicy));
+        }
+        Ok(())
+    }
+
+    /// The auction end time including any anti-sniping extensions
+    pub fn effective_end_time(&self) -> i64 {
+        self.extended_end_time.max(self.auction_end_time)
+    }
+
+    /// Extend the auction if a bid at `now` lands within the extension window, up to the cap.
+    /// Returns whether the auction was extended.
+    pub fn extend_for_bid(&mut self, now: i64) -> bool {
+        let effective_end = self.effective_end_time();
+        if self.extension_window <= 0 || self.extension_duration <= 0 {
+            return false;
+        }
+        if now < effective_end.saturating_sub(self.extension_window) || now > effective_end {
+            return false;
+        }
+        let capped_end = self.auction_end_time.saturating_add(self.max_extension);
+        let new_end = effective_end.saturating_add(self.extension_duration).min(capped_end);
+        if new_end <= effective_end {
+            return false;
+        }
+        self.extended_end_time = new_end;
+        true
+    }
 
     /// Calculate the current auction price based on the event parameters and the given timestamp.
     pub fn get_current_auction_price(&self, now: i64) -> u64 {
-        if now <= self.auction_start_time {
-            self.start_price
-        } else if now >= self.auction_end_time {
-            self.end_price
+        dutch_auction_price(
+            self.start_price,
+            self.end_price,
+            self.auction_start_time,
+            self.effective_end_time(),
+            now,
+        )
+    }
+
+    /// Calculate the current auction price of a ticket tier, or None if the tier doesn't exist.
+    pub fn get_tier_price(&self, tier: u8, now: i64) -> Option<u64> {
+        self.tiers.get(tier as usize).map(|ticket_tier| {
+            dutch_auction_price(
+                ticket_tier.start_price,
+                ticket_tier.end_price,
+                self.auction_start_time,
+                self.effective_end_time(),
+                now,
+            )
+        })
+    }
+
+    /// Price a bid in `tier` is placed at: the current price, as long as the bidder's maximum covers it.
+    /// The price moves every second, so bidders name a ceiling rather than the exact price.
+    pub fn bid_price(&self, tier: u8, max_price: u64, now: i64) -> Result<u64> {
+        let current_price = self
+            .get_tier_price(tier, now)
+            .ok_or(error!(ErrorCode::InvalidTicketTier))?;
+        if max_price < current_price {
+            return Err(error!(ErrorCode::CurrentPriceAboveMax));
+        }
+        Ok(current_price)
+    }
+
+    /// Check a limit bid in `tier` is worth leaving for a keeper: below the current price
+    /// (otherwise it should just be placed), but no lower than the tier's end price (which it would never reach)
+    pub fn validate_limit_price(&self, tier: u8, limit_price: u64, now: i64) -> Result<()> {
+        let ticket_tier = self.tiers.get(tier as usize).ok_or(error!(ErrorCode::InvalidTicketTier))?;
+        if self.bid_price(tier, limit_price, now).is_ok() || limit_price < ticket_tier.end_price {
+            return Err(error!(ErrorCode::InvalidLimitPrice));
+        }
+        Ok(())
+    }
+
+    /// The price a ticket tier closed at, or None if the tier doesn't exist
+    pub fn tier_close_price(&self, tier: u8) -> Option<u64> {
+        self.tiers.get(tier as usize).map(|ticket_tier| ticket_tier.close_price)
+    }
+
+    /// Check if an auto-activating event has reached its auction start time but is still Created
+    pub fn is_due_for_activation(&self, now: i64) -> bool {
+        self.status == EVENT_STATUS_CREATED &&
+        self.auto_activate &&
+        now >= self.auction_start_time
+    }
+
+    /// Activate the event if it is due for auto-activation. Returns whether it was activated.
+    pub fn activate_if_due(&mut self, now: i64) -> bool {
+        if self.is_due_for_activation(now) {
+            self.status = EVENT_STATUS_ACTIVE;
+            true
         } else {
-            let elapsed = now - self.auction_start_time;
-            let duration = self.auction_end_time - self.auction_start_time;
-            let price_diff = self.start_price.saturating_sub(self.end_price);
-            self.start_price - ((price_diff as i64 * elapsed) / duration) as u64
+            false
         }
     }
 
@@ -66,13 +431,296 @@ impl Event {
     pub fn is_active_for_bidding(&self, now: i64) -> bool {
         self.status == EVENT_STATUS_ACTIVE && 
         now >= self.auction_start_time && 
-        now <= self.auction_end_time
+        now <= self.effective_end_time()
     }
 
     /// Check if the auction is in a valid state for finalizing (setting close price)
     pub fn can_finalize(&self, now: i64) -> bool {
         self.status == EVENT_STATUS_ACTIVE && 
-        now >= self.auction_end_time &&
+        now >= self.effective_end_time() &&
+        (!self.is_sealed_bid() || now > self.reveal_end_time) &&
         self.auction_close_price == 0
     }
+
+    /// Finalize the auction at `now`. If not enough tickets were sold the event fails and every bid
+    /// becomes fully refundable; otherwise each tier closes at its own price (`close_price` for tier 0,
+    /// `tier_close_prices` for tiers 1.. in tier order) and winners are settled against it.
+    pub fn finalize(&mut self, close_price: u64, tier_close_prices: Vec<u64>, now: i64) -> Result<()> {
+        // Check if the auction can be finalized
+        if !self.can_finalize(now) {
+            return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+        }
+        
+        // If not enough tickets were sold, the event fails and every bid becomes fully refundable
+        if !self.meets_minimum_sales() {
+            msg!(
+                "Minimum sales not met ({} of {} tickets) - event failed",
+                self.tickets_awarded,
+                self.min_tickets_sold
+            );
+            self.status = EVENT_STATUS_FAILED;
+            return Ok(());
+        }
+        
+        // Validate close price is between start and end prices, or for sealed bids, that it
+        // clears every winning bid and is no lower than the reserve
+        if self.is_sealed_bid() {
+            let above_winning_bid = self.tickets_awarded > 0 && close_price > self.lowest_winning_bid;
+            if close_price < self.sealed_deposit || above_winning_bid {
+                return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+            }
+        } else if close_price > self.start_price || close_price < self.end_price {
+            return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+        }
+        if !self.tiers.first().is_none_or(|base_tier| base_tier.clears_awarded_bids(close_price)) {
+            return Err(error!(ErrorCode::ClosePriceAboveWinningBid));
+        }
+        
+        // Every additional tier closes at its own price, also within its own price range and
+        // no higher than any bid awarded in that tier paid
+        if tier_close_prices.len() != self.tiers.len().saturating_sub(1) {
+            return Err(error!(ErrorCode::TierClosePricesMismatch));
+        }
+        for (ticket_tier, tier_close_price) in self.tiers.iter().skip(1).zip(&tier_close_prices) {
+            if *tier_close_price > ticket_tier.start_price || *tier_close_price < ticket_tier.end_price {
+                return Err(error!(ErrorCode::CustomError)); // Replace with specific error
+            }
+            if !ticket_tier.clears_awarded_bids(*tier_close_price) {
+                return Err(error!(ErrorCode::ClosePriceAboveWinningBid));
+            }
+        }
+        for (ticket_tier, tier_close_price) in self.tiers.iter_mut().skip(1).zip(tier_close_prices) {
+            ticket_tier.close_price = tier_close_price;
+        }
+        
+        // Set the auction close price
+        self.auction_close_price = close_price;
+        if let Some(base_tier) = self.tiers.first_mut() {
+            base_tier.close_price = close_price;
+        }
+        
+        // Update event status to finalized
+        self.status = EVENT_STATUS_FINALIZED;
+        
+        Ok(())
+    }
+
+    /// Check if this is a sealed-bid commit-reveal auction rather than a Dutch auction
+    pub fn is_sealed_bid(&self) -> bool {
+        self.auction_type == AUCTION_TYPE_SEALED
+    }
+
+    /// Which phase the auction is in at `now`: Dutch auctions go straight from the bidding
+    /// (commit) phase to closed, sealed-bid auctions have a reveal phase in between
+    pub fn auction_phase(&self, now: i64) -> u8 {
+        if now < self.auction_start_time {
+            AUCTION_PHASE_NOT_STARTED
+        } else if now <= self.effective_end_time() {
+            AUCTION_PHASE_COMMIT
+        } else if self.is_sealed_bid() && now <= self.reveal_end_time {
+            AUCTION_PHASE_REVEAL
+        } else {
+            AUCTION_PHASE_CLOSED
+        }
+    }
+
+    /// Check if bids for this event are paid in an SPL token rather than SOL
+    pub fn is_token_denominated(&self) -> bool {
+        self.payment_mint != Pubkey::default()
+    }
+
+    /// Total proceeds owed to the organizer once the auction is finalized, including the deposits
+    /// slashed from sealed bids that were never revealed
+    pub fn proceeds(&self) -> Option<u64> {
+        self.tiers.iter().try_fold(self.unrevealed_slashes, |total, ticket_tier| {
+            total.checked_add((ticket_tier.awarded as u64).checked_mul(ticket_tier.proceeds_price())?)
+        })
+    }
+
+    /// Check a payee table is well formed before it is stored on the event
+    pub fn validate_payees(payees: &[Payee], dust_payee: u8) -> Result<()> {
+        if payees.is_empty() || payees.len() > MAX_PAYEES || dust_payee as usize >= payees.len() {
+            return Err(error!(ErrorCode::InvalidPayeeShares));
+        }
+        
+        let mut total_bps: u32 = 0;
+        for (index, payee) in payees.iter().enumerate() {
+            if payee.share_bps == 0 || payees[..index].iter().any(|other| other.recipient == payee.recipient) {
+                return Err(error!(ErrorCode::InvalidPayeeShares));
+            }
+            total_bps += payee.share_bps as u32;
+        }
+        if total_bps != TOTAL_BASIS_POINTS as u32 {
+            return Err(error!(ErrorCode::InvalidPayeeShares));
+        }
+        
+        Ok(())
+    }
+
+    /// Split proceeds between the payees by share, giving rounding dust to the dust payee
+    pub fn payee_amounts(&self, proceeds: u64) -> Vec<u64> {
+        let mut amounts: Vec<u64> = self
+            .payees
+            .iter()
+            .map(|payee| (proceeds as u128 * payee.share_bps as u128 / TOTAL_BASIS_POINTS as u128) as u64)
+            .collect();
+        let dust = proceeds - amounts.iter().sum::<u64>();
+        if let Some(dust_amount) = amounts.get_mut(self.dust_payee as usize) {
+            *dust_amount += dust;
+        }
+        amounts
+    }
+
+    /// Check nothing is still owed to bidders or payees, so the event can be closed
+    pub fn is_fully_settled(&self) -> bool {
+        if self.bids_open > 0 {
+            return false;
+        }
+        match self.status {
+            EVENT_STATUS_CREATED | EVENT_STATUS_FAILED | EVENT_STATUS_CANCELLED => true,
+            EVENT_STATUS_FINALIZED => self.proceeds_withdrawn || self.proceeds() == Some(0),
+            _ => false,
+        }
+    }
+
+    /// Number of tickets still to be awarded in `tier`, limited by what's left of the event's total supply
+    pub fn tickets_left(&self, tier: u8) -> u32 {
+        self.tiers.get(tier as usize).map_or(0, |ticket_tier| {
+            ticket_tier
+                .supply
+                .saturating_sub(ticket_tier.awarded)
+                .min(self.ticket_supply.saturating_sub(self.tickets_awarded))
+        })
+    }
+
+    /// Most assets the inventory can hold: those already awarded plus one for every ticket left to award
+    pub fn asset_capacity(&self) -> u32 {
+        self.assets_awarded
+            .saturating_add(self.ticket_supply.saturating_sub(self.tickets_awarded))
+    }
+
+    /// Return `quantity` released tickets to `tier` when nobody is waiting for them, so they can be
+    /// awarded again. Their assets don't go back in the inventory; it makes room for new ones instead.
+    pub fn return_tickets(&mut self, tier: u8, quantity: u8) -> Result<()> {
+        let ticket_tier = self
+            .tiers
+            .get_mut(tier as usize)
+            .ok_or(error!(ErrorCode::InvalidTicketTier))?;
+        ticket_tier.awarded = ticket_tier
+            .awarded
+            .checked_sub(quantity as u32)
+            .ok_or(error!(ErrorCode::CustomError))?;
+        self.tickets_awarded = self
+            .tickets_awarded
+            .checked_sub(quantity as u32)
+            .ok_or(error!(ErrorCode::CustomError))?;
+        Ok(())
+    }
+
+    /// Check if a new bid in `tier` would have to join the waitlist because no tickets are left for it
+    pub fn is_sold_out(&self, tier: u8) -> bool {
+        self.tickets_awarded >= self.ticket_supply ||
+        self.tiers.get(tier as usize).is_some_and(|ticket_tier| ticket_tier.is_sold_out())
+    }
+
+    /// Check if a bid for `amount` in `tier` is in the pool a lottery draws winners from
+    pub fn is_lottery_pool_bid(&self, tier: u8, amount: u64) -> bool {
+        tier == 0 && self.tiers.first().is_some_and(|base_tier| amount == base_tier.end_price)
+    }
+
+    /// Check if a bid for `amount` in `tier` outbids the lottery pool, so it's awarded ahead of any draw
+    pub fn is_above_lottery_pool(&self, tier: u8, amount: u64) -> bool {
+        tier == 0 && !self.is_lottery_pool_bid(tier, amount)
+    }
+
+    /// Tier 0 tickets a lottery can draw winners for: whatever hasn't been awarded, less the tickets
+    /// held back for pending bids above the pool, which outbid it
+    pub fn lottery_tickets_available(&self) -> u32 {
+        self.tiers.first().map_or(0, |base_tier| {
+            base_tier
+                .supply
+                .saturating_sub(base_tier.awarded)
+                .saturating_sub(self.tickets_bid_above_pool)
+        })
+    }
+
+    /// Check if the organizer has committed to drawing lottery winners
+    pub fn has_lottery(&self) -> bool {
+        self.lottery_randomness != Pubkey::default()
+    }
+
+    /// Fee owed to the organizer for withdrawing a bid of `amount` at `now`: free before the cutoff,
+    /// the withdrawal fee after it, and not allowed at all once award processing has started
+    pub fn withdrawal_fee(&self, amount: u64, now: i64) -> Result<u64> {
+        if self.award_processing_started {
+            return Err(error!(ErrorCode::WithdrawalsClosed));
+        }
+        if self.withdrawal_cutoff == 0 || now < self.withdrawal_cutoff {
+            return Ok(0);
+        }
+        Ok((amount as u128 * self.withdrawal_fee_bps as u128 / TOTAL_BASIS_POINTS as u128) as u64)
+    }
+
+    /// Part of an unrevealed sealed bid's `deposit` kept by the event rather than refunded
+    pub fn unrevealed_slash(&self, deposit: u64) -> u64 {
+        (deposit as u128 * self.unrevealed_slash_bps as u128 / TOTAL_BASIS_POINTS as u128) as u64
+    }
+
+    /// Check if any of the event's tickets are listed for resale. Listings can only be cancelled or
+    /// bought while the event account exists, so the event can't be closed until they're gone.
+    pub fn has_open_listings(&self) -> bool {
+        self.listings_open > 0
+    }
+
+    /// Check if the event's tickets can change hands: only while it's running or once it has been
+    /// finalized, as a failed or cancelled event's tickets have been refunded
+    pub fn can_transfer_tickets(&self) -> bool {
+        self.status == EVENT_STATUS_ACTIVE || self.status == EVENT_STATUS_FINALIZED
+    }
+
+    /// Royalty owed to the organizer when a ticket in `tier` for `quantity` admissions is resold for
+    /// `price`, checking the event's transfer policy allows the transfer at that price
+    pub fn resale_royalty(&self, price: u64, tier: u8, quantity: u8) -> Result<u64> {
+        match self.transfer_policy {
+            TRANSFER_POLICY_FREE => {}
+            TRANSFER_POLICY_CAPPED => {
+                let close_price = self.tier_close_price(tier).ok_or(error!(ErrorCode::InvalidTicketTier))?;
+                let max_price = close_price as u128 * quantity as u128 * self.resale_cap_bps as u128
+                    / TOTAL_BASIS_POINTS as u128;
+                if price as u128 > max_price {
+                    return Err(error!(ErrorCode::ResalePriceAboveCap));
+                }
+            }
+            _ => return Err(error!(ErrorCode::TicketNotTransferable)),
+        }
+        Ok((price as u128 * self.royalty_bps as u128 / TOTAL_BASIS_POINTS as u128) as u64)
+    }
+
+    /// Hand out the sequence number for a newly placed bid
+    pub fn next_bid_sequence(&mut self) -> Result<u64> {
+        let sequence = self.bids_placed;
+        self.bids_placed = sequence.checked_add(1).ok_or(error!(ErrorCode::CustomError))?;
+        Ok(sequence)
+    }
+
+    /// How many of a bid's tickets a sequential award hands out: as many as it asked for that are
+    /// still left in its tier, overall, and under the wallet cap (0 if it can never be awarded)
+    pub fn sequential_award_quantity(&self, tier: u8, quantity: u8, tickets_held: u32) -> u8 {
+        let mut available = self.tickets_left(tier);
+        if self.max_tickets_per_wallet > 0 {
+            available = available.min(self.max_tickets_per_wallet.saturating_sub(tickets_held));
+        }
+        available.min(quantity as u32) as u8
+    }
+
+    /// Check if a wallet already holding `held` tickets may acquire `additional` more
+    pub fn within_wallet_cap(&self, held: u32, additional: u32) -> bool {
+        self.max_tickets_per_wallet == 0 ||
+        held.saturating_add(additional) <= self.max_tickets_per_wallet
+    }
+
+    /// Check if enough tickets have been awarded for the event to go ahead
+    pub fn meets_minimum_sales(&self) -> bool {
+        self.tickets_awarded >= self.min_tickets_sold
+    }
 }
\ No newline at end of file
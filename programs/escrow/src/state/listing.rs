@@ -0,0 +1,28 @@
+//! Ticketfair Listing account definition
+
+use anchor_lang::prelude::*;
+
+/// A ticket for sale on the resale marketplace. The listing holds the ticket in escrow
+/// (it is the ticket's owner) until the ticket is bought or the listing is cancelled.
+#[account]
+#[derive(Default)]
+pub struct Listing {
+    /// Holder who listed the ticket and is paid when it sells
+    pub seller: Pubkey,
+    pub event: Pubkey,
+    pub ticket: Pubkey,
+    /// SPL token mint the price is paid in (Pubkey::default() for native SOL)
+    pub payment_mint: Pubkey,
+    /// Asking price for the whole ticket
+    pub price: u64,
+    pub bump: u8,
+}
+
+impl Listing {
+    pub const INIT_SPACE: usize = 32 + 32 + 32 + 32 + 8 + 1;
+
+    /// Check if the listing is priced in an SPL token rather than native SOL
+    pub fn is_token_denominated(&self) -> bool {
+        self.payment_mint != Pubkey::default()
+    }
+}
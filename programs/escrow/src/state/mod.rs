@@ -3,9 +3,15 @@ pub mod event;
 pub mod ticket;
 pub mod user;
 pub mod bid;
+pub mod ticket_inventory;
+pub mod randomness;
+pub mod listing;
 
 pub use offer::*;
 pub use event::*;
 pub use ticket::*;
 pub use user::*;
 pub use bid::*;
+pub use ticket_inventory::*;
+pub use randomness::*;
+pub use listing::*;
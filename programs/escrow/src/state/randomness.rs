@@ -0,0 +1,134 @@
+//! Randomness sources for Ticketfair lotteries
+//!
+//! Lotteries read randomness through the RandomnessProvider trait. On-chain it is backed by
+//! Switchboard On-Demand randomness accounts; LocalRandomness stands in for it in offline tests.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use crate::error::ErrorCode;
+
+/// Switchboard On-Demand program that owns randomness accounts
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey = pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+
+/// A source of verifiable randomness that is committed to before it is revealed
+pub trait RandomnessProvider {
+    /// Slot the randomness was committed at
+    fn seed_slot(&self) -> u64;
+    /// Whether the random value has already been revealed (and so could have been seen by the caller)
+    fn is_revealed(&self) -> bool;
+    /// The revealed random value, readable only in the slot it was revealed in
+    fn reveal_value(&self, current_slot: u64) -> Result<[u8; 32]>;
+}
+
+/// Switchboard On-Demand `RandomnessAccountData`, read straight from the account bytes
+pub struct SwitchboardRandomness {
+    pub authority: Pubkey,
+    pub queue: Pubkey,
+    pub seed_slothash: [u8; 32],
+    pub seed_slot: u64,
+    pub oracle: Pubkey,
+    pub reveal_slot: u64,
+    pub value: [u8; 32],
+}
+
+impl SwitchboardRandomness {
+    /// 8 byte discriminator + authority + queue + seed_slothash + seed_slot + oracle + reveal_slot + value + reserved
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 32 + 8 + 32 + 96 + 128;
+
+    /// Parse a randomness account, checking it is owned by Switchboard and has the right discriminator
+    pub fn try_from_account(account: &AccountInfo) -> Result<Self> {
+        if account.owner != &SWITCHBOARD_ON_DEMAND_PROGRAM_ID {
+            return Err(error!(ErrorCode::InvalidRandomnessAccount));
+        }
+        Self::try_from_slice(&account.try_borrow_data()?)
+    }
+
+    /// Parse the account layout from raw account data
+    pub fn try_from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN || data[..8] != Self::discriminator() {
+            return Err(error!(ErrorCode::InvalidRandomnessAccount));
+        }
+        let pubkey_at = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+        let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        Ok(Self {
+            authority: pubkey_at(8),
+            queue: pubkey_at(40),
+            seed_slothash: data[72..104].try_into().unwrap(),
+            seed_slot: u64_at(104),
+            oracle: pubkey_at(112),
+            reveal_slot: u64_at(144),
+            value: data[152..184].try_into().unwrap(),
+        })
+    }
+
+    /// Anchor discriminator of Switchboard's RandomnessAccountData
+    pub fn discriminator() -> [u8; 8] {
+        hash(b"account:RandomnessAccountData").to_bytes()[..8].try_into().unwrap()
+    }
+}
+
+impl RandomnessProvider for SwitchboardRandomness {
+    fn seed_slot(&self) -> u64 {
+        self.seed_slot
+    }
+
+    fn is_revealed(&self) -> bool {
+        self.reveal_slot != 0
+    }
+
+    fn reveal_value(&self, current_slot: u64) -> Result<[u8; 32]> {
+        // Switchboard only guarantees the value in the slot the oracle revealed it
+        if current_slot != self.reveal_slot {
+            return Err(error!(ErrorCode::RandomnessNotResolved));
+        }
+        Ok(self.value)
+    }
+}
+
+/// Fixed randomness for testing lotteries without a Switchboard oracle
+pub struct LocalRandomness {
+    pub seed_slot: u64,
+    pub reveal_slot: u64,
+    pub value: [u8; 32],
+}
+
+impl RandomnessProvider for LocalRandomness {
+    fn seed_slot(&self) -> u64 {
+        self.seed_slot
+    }
+
+    fn is_revealed(&self) -> bool {
+        self.reveal_slot != 0
+    }
+
+    fn reveal_value(&self, current_slot: u64) -> Result<[u8; 32]> {
+        // Same rule as Switchboard, so a lottery that works against this works against the oracle
+        if self.reveal_slot == 0 || current_slot != self.reveal_slot {
+            return Err(error!(ErrorCode::RandomnessNotResolved));
+        }
+        Ok(self.value)
+    }
+}
+
+/// Deterministically pick lottery winners. Each entry is ranked by hashing the random value with its
+/// bid address, so the result doesn't depend on the order entries are passed in. Entries are then
+/// taken in rank order while their ticket quantity still fits in the tickets available.
+pub fn draw_lottery(random_value: &[u8; 32], entries: &[(Pubkey, u8)], tickets_available: u32) -> Vec<bool> {
+    let mut ranked: Vec<(usize, [u8; 32])> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, (bid, _))| (index, hashv(&[random_value, bid.as_ref()]).to_bytes()))
+        .collect();
+    ranked.sort_by_key(|(_, rank)| *rank);
+
+    let mut winners = vec![false; entries.len()];
+    let mut remaining = tickets_available;
+    for (index, _) in ranked {
+        let quantity = entries[index].1 as u32;
+        if quantity <= remaining {
+            winners[index] = true;
+            remaining -= quantity;
+        }
+    }
+    winners
+}
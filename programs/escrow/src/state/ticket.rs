@@ -4,6 +4,7 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 
 #[account]
+#[derive(Default)]
 pub struct Ticket {
     pub owner: Pubkey,
     pub event: Pubkey,
@@ -12,10 +13,21 @@ pub struct Ticket {
     pub bump: u8,
     /// The cNFT asset ID for this ticket (Bubblegum)
     pub cnft_asset_id: Pubkey,
+    /// Number of admissions this ticket is good for
+    pub quantity: u8,
+    /// Index of the event ticket tier this ticket belongs to
+    pub tier: u8,
+    /// Position of this ticket's first asset in the event inventory (one asset per admission)
+    pub inventory_index: u32,
+    /// Wallet the ticket was issued to, which its address is derived from (owner changes on transfer)
+    pub original_owner: Pubkey,
+    /// Whether the ticket was awarded to a bid, so it was paid for and counted against supply;
+    /// tickets from buy_ticket weren't, so they can't change hands
+    pub awarded: bool,
 }
 
 impl Ticket {
-    pub const INIT_SPACE: usize = 32 + 32 + 1 + 4 + MAX_METADATA_URL_LEN + 1 + 32;
+    pub const INIT_SPACE: usize = 32 + 32 + 1 + 4 + MAX_METADATA_URL_LEN + 1 + 32 + 1 + 1 + 4 + 32 + 1;
     
     /// Check if the ticket can be claimed
     pub fn can_claim(&self) -> bool {
@@ -28,4 +40,9 @@ impl Ticket {
         // Only owned tickets can be refunded
         self.status == TICKET_STATUS_OWNED
     }
+    
+    /// Check if the ticket has been transferred, resold or listed since it was issued
+    pub fn has_changed_hands(&self) -> bool {
+        self.owner != self.original_owner
+    }
 }
\ No newline at end of file
@@ -0,0 +1,59 @@
+//! Ticketfair ticket inventory account definition
+//!
+//! cNFT asset IDs for an event's tickets are stored in fixed-size zero-copy pages so that
+//! events with 1000+ tickets fit within account size and compute limits.
+
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+#[account(zero_copy)]
+#[repr(C)]
+pub struct TicketInventoryPage {
+    /// The event this page of inventory belongs to
+    pub event: Pubkey,
+    /// Position of this page in the event's inventory (ticket index / TICKETS_PER_INVENTORY_PAGE)
+    pub page_index: u32,
+    /// Number of asset IDs registered in this page so far
+    pub asset_count: u32,
+    /// cNFT asset IDs, one per ticket (Pubkey::default() until registered)
+    pub asset_ids: [Pubkey; TICKETS_PER_INVENTORY_PAGE],
+}
+
+impl TicketInventoryPage {
+    pub const INIT_SPACE: usize = 32 + 4 + 4 + (32 * TICKETS_PER_INVENTORY_PAGE);
+
+    /// Number of inventory pages needed to hold the given ticket supply
+    pub fn page_count(ticket_supply: u32) -> u32 {
+        ticket_supply.div_ceil(TICKETS_PER_INVENTORY_PAGE as u32)
+    }
+
+    /// Page index and slot within that page for a ticket index
+    pub fn locate(ticket_index: u32) -> (u32, usize) {
+        let page_size = TICKETS_PER_INVENTORY_PAGE as u32;
+        (ticket_index / page_size, (ticket_index % page_size) as usize)
+    }
+
+    /// Write a chunk of asset IDs into this page, starting at the next unfilled slot
+    pub fn register_assets(&mut self, slot: usize, asset_ids: &[Pubkey]) -> Result<()> {
+        if slot != self.asset_count as usize {
+            return Err(error!(ErrorCode::InvalidAssetRegistration));
+        }
+        let end = slot
+            .checked_add(asset_ids.len())
+            .filter(|&end| end <= TICKETS_PER_INVENTORY_PAGE)
+            .ok_or(error!(ErrorCode::InvalidAssetRegistration))?;
+        self.asset_ids[slot..end].copy_from_slice(asset_ids);
+        self.asset_count = end as u32;
+        Ok(())
+    }
+
+    /// Asset ID registered at a slot, if any
+    pub fn asset_at(&self, slot: usize) -> Option<Pubkey> {
+        if slot < self.asset_count as usize {
+            Some(self.asset_ids[slot])
+        } else {
+            None
+        }
+    }
+}
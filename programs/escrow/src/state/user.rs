@@ -1,16 +1,66 @@
 //! Ticketfair User account definition
 
 use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+/// Number of tickets a user holds for a single event
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct EventHolding {
+    pub event: Pubkey,
+    pub tickets: u32,
+}
+
+impl EventHolding {
+    pub const INIT_SPACE: usize = 32 + 4;
+}
 
 #[account]
+#[derive(Default)]
 pub struct User {
     pub authority: Pubkey,
     pub tickets_purchased: u32,
     pub events_created: u32,
     pub bump: u8,
+    /// Tickets held per event, used to enforce per-wallet purchase caps. Accounts created
+    /// before this field existed must be resized with migrate_user before they can be loaded.
+    pub event_holdings: Vec<EventHolding>, // #[max_len = MAX_EVENT_HOLDINGS_PER_USER]
     // Add more fields as needed
 }
 
 impl User {
-    pub const INIT_SPACE: usize = 32 + 4 + 4 + 1;
-} 
\ No newline at end of file
+    pub const INIT_SPACE: usize = 32 + 4 + 4 + 1 +
+                                  4 + (EventHolding::INIT_SPACE * MAX_EVENT_HOLDINGS_PER_USER);
+
+    /// Number of tickets this user holds for the given event
+    pub fn tickets_held(&self, event: &Pubkey) -> u32 {
+        self.event_holdings
+            .iter()
+            .find(|holding| holding.event == *event)
+            .map_or(0, |holding| holding.tickets)
+    }
+
+    /// Record newly acquired tickets for the given event
+    pub fn record_tickets(&mut self, event: &Pubkey, quantity: u32) -> Result<()> {
+        match self.event_holdings.iter_mut().find(|holding| holding.event == *event) {
+            Some(holding) => {
+                holding.tickets = holding.tickets.checked_add(quantity).ok_or(error!(ErrorCode::CustomError))?;
+            }
+            None => {
+                if self.event_holdings.len() >= MAX_EVENT_HOLDINGS_PER_USER {
+                    return Err(error!(ErrorCode::UserHoldingsFull));
+                }
+                self.event_holdings.push(EventHolding { event: *event, tickets: quantity });
+            }
+        }
+        self.tickets_purchased = self.tickets_purchased.checked_add(quantity).ok_or(error!(ErrorCode::CustomError))?;
+        Ok(())
+    }
+
+    /// Record tickets for the given event that the user no longer holds
+    pub fn release_tickets(&mut self, event: &Pubkey, quantity: u32) {
+        if let Some(holding) = self.event_holdings.iter_mut().find(|holding| holding.event == *event) {
+            holding.tickets = holding.tickets.saturating_sub(quantity);
+        }
+    }
+}
@@ -0,0 +1,642 @@
+// Account validation tests: run the instruction account constraints against hand-built accounts
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::solana_program::system_program;
+use anchor_lang::Bumps;
+use escrow::constants::*;
+use escrow::handlers::{
+    buy_listing, draw_winners, rank_sealed_bids, release_ticket, transfer_ticket, AwardTicketAccountConstraints, BuyListingAccountConstraints, CancelListingAccountConstraints,
+    DrawWinnersAccountConstraints, MigrateUserAccountConstraints, RankSealedBidsAccountConstraints, RefundBidAccountConstraints,
+    ReleaseTicketAccountConstraints, TransferTicketAccountConstraints,
+};
+use escrow::state::{
+    Bid, Event, Listing, SwitchboardRandomness, Ticket, TicketInventoryPage, TicketTier, User,
+    SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+};
+use std::collections::BTreeSet;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A test account that owns the storage its AccountInfo borrows
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        is_signer: bool,
+        is_writable: bool,
+        executable: bool,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey, owner: Pubkey) -> Self {
+            TestAccount { key, owner, lamports: 1_000_000_000, data: vec![], is_signer: false, is_writable: true, executable: false }
+        }
+
+        fn signer(key: Pubkey) -> Self {
+            TestAccount { is_signer: true, ..Self::new(key, system_program::ID) }
+        }
+
+        fn program_owned<T: AccountSerialize>(key: Pubkey, account: &T) -> Self {
+            let mut data = vec![];
+            account.try_serialize(&mut data).unwrap();
+            TestAccount { data, ..Self::new(key, escrow::ID) }
+        }
+
+        fn system_program() -> Self {
+            TestAccount { executable: true, is_writable: false, ..Self::new(system_program::ID, Pubkey::default()) }
+        }
+
+        // Optional accounts are omitted by passing the program id
+        fn omitted() -> Self {
+            TestAccount { is_writable: false, ..Self::new(escrow::ID, Pubkey::default()) }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                self.is_signer,
+                self.is_writable,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                self.executable,
+                0,
+            )
+        }
+    }
+
+    fn test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn event_address(organizer: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"event", organizer.as_ref()], &escrow::ID)
+    }
+
+    fn bid_address(event: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"bid", event.as_ref(), bidder.as_ref()], &escrow::ID)
+    }
+
+    fn escrow_address(event: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"escrow", event.as_ref()], &escrow::ID).0
+    }
+
+    fn test_event(organizer: Pubkey) -> Event {
+        Event {
+            organizer,
+            bump: event_address(&organizer).1,
+            status: EVENT_STATUS_FINALIZED,
+            ..Default::default()
+        }
+    }
+
+    fn test_bid(event: Pubkey, bidder: Pubkey) -> Bid {
+        Bid {
+            bidder,
+            event,
+            amount: 1_000_000_000,
+            status: BID_STATUS_PENDING,
+            bump: bid_address(&event, &bidder).1,
+            quantity: 1,
+            ..Default::default()
+        }
+    }
+
+    // Run an instruction's account constraints the way the program entrypoint does
+    fn validate<'info, T: Accounts<'info, B> + Bumps<Bumps = B>, B: Default>(infos: &'info [AccountInfo<'info>]) -> Result<T> {
+        let mut accounts = infos;
+        T::try_accounts(&escrow::ID, &mut accounts, &[], &mut B::default(), &mut BTreeSet::new())
+    }
+
+    fn assert_rejected<T>(result: Result<T>, expected: u32) {
+        match result {
+            Ok(_) => panic!("accounts should have been rejected"),
+            Err(Error::AnchorError(error)) => assert_eq!(error.error_code_number, expected),
+            Err(error) => panic!("unexpected error: {error:?}"),
+        }
+    }
+
+    // Accounts for refund_bid on a SOL event: bidder, event, bid, event_pda, 4 omitted token accounts, system program
+    fn refund_accounts(signer: Pubkey, event_key: Pubkey, event: &Event, bid_key: Pubkey, bid: &Bid) -> Vec<TestAccount> {
+        let mut accounts = vec![
+            TestAccount::signer(signer),
+            TestAccount::program_owned(event_key, event),
+            TestAccount::program_owned(bid_key, bid),
+            TestAccount::new(escrow_address(&event_key), system_program::ID),
+        ];
+        accounts.extend((0..4).map(|_| TestAccount::omitted()));
+        accounts.push(TestAccount::system_program());
+        accounts
+    }
+
+    const CONSTRAINT_SEEDS: u32 = 2006;
+
+    fn relationship_mismatch() -> u32 {
+        escrow::error::ErrorCode::AccountRelationshipMismatch.into()
+    }
+
+    #[test]
+    fn test_refund_accepts_own_bid() {
+        let organizer = test_pubkey(1);
+        let bidder = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+        let bid_key = bid_address(&event_key, &bidder).0;
+
+        let mut accounts = refund_accounts(bidder, event_key, &test_event(organizer), bid_key, &test_bid(event_key, bidder));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert!(validate::<RefundBidAccountConstraints, _>(&infos).is_ok());
+    }
+
+    #[test]
+    fn test_refund_rejects_another_bidders_bid() {
+        let organizer = test_pubkey(1);
+        let victim = test_pubkey(2);
+        let attacker = test_pubkey(3);
+        let event_key = event_address(&organizer).0;
+        let bid_key = bid_address(&event_key, &victim).0;
+
+        // The attacker signs for a refund of the victim's bid
+        let mut accounts = refund_accounts(attacker, event_key, &test_event(organizer), bid_key, &test_bid(event_key, victim));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<RefundBidAccountConstraints, _>(&infos), CONSTRAINT_SEEDS);
+    }
+
+    #[test]
+    fn test_refund_rejects_forged_bid_owner() {
+        let organizer = test_pubkey(1);
+        let victim = test_pubkey(2);
+        let attacker = test_pubkey(3);
+        let event_key = event_address(&organizer).0;
+
+        // A bid at the attacker's address that claims to belong to someone else
+        let (bid_key, bump) = bid_address(&event_key, &attacker);
+        let bid = Bid { bump, ..test_bid(event_key, victim) };
+        let mut accounts = refund_accounts(attacker, event_key, &test_event(organizer), bid_key, &bid);
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<RefundBidAccountConstraints, _>(&infos), relationship_mismatch());
+    }
+
+    #[test]
+    fn test_refund_rejects_bid_from_another_event() {
+        let bidder = test_pubkey(2);
+        let cheap_organizer = test_pubkey(4);
+        let cheap_event_key = event_address(&cheap_organizer).0;
+        let organizer = test_pubkey(1);
+        let event_key = event_address(&organizer).0;
+
+        // The bid was placed on another event, but is refunded against this one
+        let bid_key = bid_address(&cheap_event_key, &bidder).0;
+        let mut accounts = refund_accounts(bidder, event_key, &test_event(organizer), bid_key, &test_bid(cheap_event_key, bidder));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<RefundBidAccountConstraints, _>(&infos), CONSTRAINT_SEEDS);
+    }
+
+    #[test]
+    fn test_refund_rejects_event_not_at_its_address() {
+        let organizer = test_pubkey(1);
+        let bidder = test_pubkey(2);
+
+        // An event account that isn't at the organizer's event address
+        let event_key = test_pubkey(9);
+        let bid_key = bid_address(&event_key, &bidder).0;
+        let mut accounts = refund_accounts(bidder, event_key, &test_event(organizer), bid_key, &test_bid(event_key, bidder));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<RefundBidAccountConstraints, _>(&infos), CONSTRAINT_SEEDS);
+    }
+
+    // Serves the rent and clock sysvars and accepts CPIs, so constraints that init accounts (and
+    // handlers that read the clock) can run off-chain
+    struct InitStubs;
+
+    impl SyscallStubs for InitStubs {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            0
+        }
+
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Clock) = Clock::default() };
+            0
+        }
+    }
+
+    // Accounts for award_ticket: organizer, event, bid, ticket, omitted user, inventory page,
+    // omitted next page, the Bubblegum accounts and the system program
+    fn award_accounts(signer: Pubkey, event_key: Pubkey, event: &Event, bid_key: Pubkey, bid: &Bid) -> Vec<TestAccount> {
+        set_syscall_stubs(Box::new(InitStubs));
+        let ticket_key = Pubkey::find_program_address(&[b"ticket", event_key.as_ref(), bid.bidder.as_ref()], &escrow::ID).0;
+        let ticket = vec![0; Ticket::DISCRIMINATOR.len() + Ticket::INIT_SPACE];
+        let inventory_key = Pubkey::find_program_address(&[b"inventory", event_key.as_ref(), 0u32.to_le_bytes().as_ref()], &escrow::ID).0;
+        let mut inventory_page = TicketInventoryPage::DISCRIMINATOR.to_vec();
+        inventory_page.resize(TicketInventoryPage::DISCRIMINATOR.len() + TicketInventoryPage::INIT_SPACE, 0);
+        let mut accounts = vec![
+            TestAccount::signer(signer),
+            TestAccount::program_owned(event_key, event),
+            TestAccount::program_owned(bid_key, bid),
+            TestAccount { data: ticket, ..TestAccount::new(ticket_key, escrow::ID) },
+            TestAccount::omitted(),
+            TestAccount { data: inventory_page, ..TestAccount::new(inventory_key, escrow::ID) },
+            TestAccount::omitted(),
+        ];
+        accounts.extend((0..5).map(|seed| TestAccount::new(test_pubkey(102 + seed), system_program::ID)));
+        accounts.push(TestAccount::system_program());
+        accounts
+    }
+
+    #[test]
+    fn test_award_accepts_bid_on_organizers_event() {
+        let organizer = test_pubkey(1);
+        let bidder = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+        let bid_key = bid_address(&event_key, &bidder).0;
+
+        let mut accounts = award_accounts(organizer, event_key, &test_event(organizer), bid_key, &test_bid(event_key, bidder));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        validate::<AwardTicketAccountConstraints, _>(&infos).unwrap();
+    }
+
+    #[test]
+    fn test_award_rejects_non_organizer() {
+        let organizer = test_pubkey(1);
+        let bidder = test_pubkey(2);
+        let attacker = test_pubkey(3);
+        let event_key = event_address(&organizer).0;
+        let bid_key = bid_address(&event_key, &bidder).0;
+
+        let mut accounts = award_accounts(attacker, event_key, &test_event(organizer), bid_key, &test_bid(event_key, bidder));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<AwardTicketAccountConstraints, _>(&infos), CONSTRAINT_SEEDS);
+    }
+
+    #[test]
+    fn test_award_rejects_bid_from_another_event() {
+        let organizer = test_pubkey(1);
+        let bidder = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+        let other_event_key = event_address(&test_pubkey(4)).0;
+
+        // A bid on another event would otherwise be awarded this event's ticket
+        let bid_key = bid_address(&other_event_key, &bidder).0;
+        let mut accounts = award_accounts(organizer, event_key, &test_event(organizer), bid_key, &test_bid(other_event_key, bidder));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<AwardTicketAccountConstraints, _>(&infos), CONSTRAINT_SEEDS);
+    }
+
+    #[test]
+    fn test_award_rejects_forged_bid_event() {
+        let organizer = test_pubkey(1);
+        let bidder = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+        let other_event_key = event_address(&test_pubkey(4)).0;
+
+        // A bid at this event's address whose data says it belongs to another event
+        let (bid_key, bump) = bid_address(&event_key, &bidder);
+        let bid = Bid { bump, ..test_bid(other_event_key, bidder) };
+        let mut accounts = award_accounts(organizer, event_key, &test_event(organizer), bid_key, &bid);
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<AwardTicketAccountConstraints, _>(&infos), relationship_mismatch());
+    }
+
+    fn test_ticket(event: Pubkey, original_owner: Pubkey, owner: Pubkey) -> (Pubkey, Ticket) {
+        let (ticket_key, bump) = Pubkey::find_program_address(&[b"ticket", event.as_ref(), original_owner.as_ref()], &escrow::ID);
+        (ticket_key, Ticket { owner, event, status: TICKET_STATUS_OWNED, bump, quantity: 1, original_owner, awarded: true, ..Default::default() })
+    }
+
+    // Accounts for transfer_ticket on a SOL event: owner, new owner, event, organizer, ticket,
+    // 2 omitted user accounts, 5 omitted token accounts, system program
+    fn transfer_accounts(signer: Pubkey, new_owner: Pubkey, event_key: Pubkey, event: &Event, ticket_key: Pubkey, ticket: &Ticket) -> Vec<TestAccount> {
+        let mut accounts = vec![
+            TestAccount::signer(signer),
+            TestAccount::signer(new_owner),
+            TestAccount::program_owned(event_key, event),
+            TestAccount::new(event.organizer, system_program::ID),
+            TestAccount::program_owned(ticket_key, ticket),
+        ];
+        accounts.extend((0..7).map(|_| TestAccount::omitted()));
+        accounts.push(TestAccount::system_program());
+        accounts
+    }
+
+    #[test]
+    fn test_transfer_accepts_resold_ticket() {
+        let organizer = test_pubkey(1);
+        let bidder = test_pubkey(2);
+        let buyer = test_pubkey(3);
+        let event_key = event_address(&organizer).0;
+
+        // A ticket already resold once stays at the address derived from the original bidder
+        let (ticket_key, ticket) = test_ticket(event_key, bidder, buyer);
+        let mut accounts = transfer_accounts(buyer, test_pubkey(5), event_key, &test_event(organizer), ticket_key, &ticket);
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        validate::<TransferTicketAccountConstraints, _>(&infos).unwrap();
+    }
+
+    #[test]
+    fn test_transfer_rejects_previous_owner() {
+        let organizer = test_pubkey(1);
+        let bidder = test_pubkey(2);
+        let buyer = test_pubkey(3);
+        let event_key = event_address(&organizer).0;
+
+        // The original bidder tries to sell the ticket again after reselling it
+        let (ticket_key, ticket) = test_ticket(event_key, bidder, buyer);
+        let mut accounts = transfer_accounts(bidder, test_pubkey(5), event_key, &test_event(organizer), ticket_key, &ticket);
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<TransferTicketAccountConstraints, _>(&infos), relationship_mismatch());
+    }
+
+    #[test]
+    fn test_transfer_rejects_unawarded_ticket() {
+        set_syscall_stubs(Box::new(InitStubs));
+        let organizer = test_pubkey(1);
+        let holder = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+
+        // A ticket from buy_ticket was never paid for or counted against supply
+        let (ticket_key, ticket) = test_ticket(event_key, holder, holder);
+        let ticket = Ticket { awarded: false, ..ticket };
+        let event = Event { transfer_policy: TRANSFER_POLICY_FREE, ..test_event(organizer) };
+        let mut accounts = transfer_accounts(holder, test_pubkey(3), event_key, &event, ticket_key, &ticket);
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        let mut constraints = validate::<TransferTicketAccountConstraints, _>(&infos).unwrap();
+        let result = transfer_ticket(Context::new(&escrow::ID, &mut constraints, &[], Default::default()), 1_000_000_000);
+        assert_rejected(result, escrow::error::ErrorCode::TicketNotTransferable.into());
+    }
+
+    // A listing for a ticket, which holds the ticket in escrow while it's for sale
+    fn test_listing(event: Pubkey, seller: Pubkey, ticket_key: Pubkey, price: u64) -> (Pubkey, Listing) {
+        let (listing_key, bump) = Pubkey::find_program_address(&[b"listing", ticket_key.as_ref()], &escrow::ID);
+        (listing_key, Listing { seller, event, ticket: ticket_key, price, bump, ..Default::default() })
+    }
+
+    // Accounts for buy_listing priced in SOL: buyer, seller, event, organizer, ticket, listing,
+    // 2 omitted user accounts, 5 omitted token accounts, system program
+    fn buy_listing_accounts(buyer: Pubkey, event: &Event, ticket: (Pubkey, &Ticket), listing: (Pubkey, &Listing)) -> Vec<TestAccount> {
+        let event_key = event_address(&event.organizer).0;
+        let mut accounts = vec![
+            TestAccount::signer(buyer),
+            TestAccount::new(listing.1.seller, system_program::ID),
+            TestAccount::program_owned(event_key, event),
+            TestAccount::new(event.organizer, system_program::ID),
+            TestAccount::program_owned(ticket.0, ticket.1),
+            TestAccount::program_owned(listing.0, listing.1),
+        ];
+        accounts.extend((0..7).map(|_| TestAccount::omitted()));
+        accounts.push(TestAccount::system_program());
+        accounts
+    }
+
+    #[test]
+    fn test_buy_listing_accepts_escrowed_ticket() {
+        let organizer = test_pubkey(1);
+        let seller = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+        let (ticket_key, _) = test_ticket(event_key, seller, seller);
+        let (listing_key, listing) = test_listing(event_key, seller, ticket_key, 1_000_000_000);
+        let (_, ticket) = test_ticket(event_key, seller, listing_key);
+
+        let mut accounts = buy_listing_accounts(test_pubkey(3), &test_event(organizer), (ticket_key, &ticket), (listing_key, &listing));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        validate::<BuyListingAccountConstraints, _>(&infos).unwrap();
+    }
+
+    #[test]
+    fn test_buy_listing_rejects_failed_event() {
+        let organizer = test_pubkey(1);
+        let seller = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+        let (ticket_key, _) = test_ticket(event_key, seller, seller);
+        let (listing_key, listing) = test_listing(event_key, seller, ticket_key, 1_000_000_000);
+        let (_, ticket) = test_ticket(event_key, seller, listing_key);
+
+        // The event failed after the ticket was listed, so the seller's bid has been refunded in full
+        let event = Event { status: EVENT_STATUS_FAILED, transfer_policy: TRANSFER_POLICY_FREE, ..test_event(organizer) };
+        let mut accounts = buy_listing_accounts(test_pubkey(3), &event, (ticket_key, &ticket), (listing_key, &listing));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        let mut constraints = validate::<BuyListingAccountConstraints, _>(&infos).unwrap();
+        let result = buy_listing(Context::new(&escrow::ID, &mut constraints, &[], Default::default()), 1_000_000_000);
+        assert_rejected(result, escrow::error::ErrorCode::TicketNotTransferable.into());
+    }
+
+    #[test]
+    fn test_buy_listing_rejects_unawarded_ticket() {
+        let organizer = test_pubkey(1);
+        let seller = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+        let (ticket_key, _) = test_ticket(event_key, seller, seller);
+        let (listing_key, listing) = test_listing(event_key, seller, ticket_key, 1_000_000_000);
+        let (_, ticket) = test_ticket(event_key, seller, listing_key);
+
+        // The listed ticket came from buy_ticket, so it was never paid for
+        let ticket = Ticket { awarded: false, ..ticket };
+        let event = Event { transfer_policy: TRANSFER_POLICY_FREE, ..test_event(organizer) };
+        let mut accounts = buy_listing_accounts(test_pubkey(3), &event, (ticket_key, &ticket), (listing_key, &listing));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        let mut constraints = validate::<BuyListingAccountConstraints, _>(&infos).unwrap();
+        let result = buy_listing(Context::new(&escrow::ID, &mut constraints, &[], Default::default()), 1_000_000_000);
+        assert_rejected(result, escrow::error::ErrorCode::TicketNotTransferable.into());
+    }
+
+    #[test]
+    fn test_buy_listing_rejects_listing_for_another_ticket() {
+        let organizer = test_pubkey(1);
+        let seller = test_pubkey(2);
+        let event_key = event_address(&organizer).0;
+
+        // The seller's cheap listing for one ticket is used to buy their other, more expensive ticket
+        let (cheap_ticket_key, _) = test_ticket(event_key, test_pubkey(4), seller);
+        let (cheap_listing_key, cheap_listing) = test_listing(event_key, seller, cheap_ticket_key, 1);
+        let (ticket_key, ticket) = test_ticket(event_key, seller, seller);
+        let mut accounts = buy_listing_accounts(test_pubkey(3), &test_event(organizer), (ticket_key, &ticket), (cheap_listing_key, &cheap_listing));
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<BuyListingAccountConstraints, _>(&infos), CONSTRAINT_SEEDS);
+    }
+
+    #[test]
+    fn test_cancel_listing_rejects_non_seller() {
+        let organizer = test_pubkey(1);
+        let seller = test_pubkey(2);
+        let attacker = test_pubkey(3);
+        let event_key = event_address(&organizer).0;
+        let (ticket_key, _) = test_ticket(event_key, seller, seller);
+        let (listing_key, listing) = test_listing(event_key, seller, ticket_key, 1_000_000_000);
+        let (_, ticket) = test_ticket(event_key, seller, listing_key);
+
+        // Cancelling would hand the escrowed ticket to whoever signs
+        let mut accounts = [
+            TestAccount::signer(attacker),
+            TestAccount::program_owned(event_key, &test_event(organizer)),
+            TestAccount::program_owned(ticket_key, &ticket),
+            TestAccount::program_owned(listing_key, &listing),
+        ];
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<CancelListingAccountConstraints, _>(&infos), relationship_mismatch());
+    }
+
+    // A user account as created before event holdings were added: authority, tickets purchased,
+    // events created and bump, with no room for the holdings
+    fn legacy_user(authority: Pubkey) -> (Pubkey, Vec<u8>) {
+        let (user_key, bump) = Pubkey::find_program_address(&[b"user", authority.as_ref()], &escrow::ID);
+        let mut data = User::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.push(bump);
+        (user_key, data)
+    }
+
+    #[test]
+    fn test_migrate_user_accepts_legacy_user_account() {
+        let authority = test_pubkey(20);
+        let (user_key, data) = legacy_user(authority);
+        assert!(User::try_deserialize(&mut &data[..]).is_err()); // Can't be loaded until it's resized
+
+        let mut accounts = [
+            TestAccount::signer(authority),
+            TestAccount { data, ..TestAccount::new(user_key, escrow::ID) },
+            TestAccount::system_program(),
+        ];
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        validate::<MigrateUserAccountConstraints, _>(&infos).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_user_rejects_another_users_account() {
+        let (user_key, data) = legacy_user(test_pubkey(20));
+
+        let mut accounts = [
+            TestAccount::signer(test_pubkey(21)),
+            TestAccount { data, ..TestAccount::new(user_key, escrow::ID) },
+            TestAccount::system_program(),
+        ];
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        assert_rejected(validate::<MigrateUserAccountConstraints, _>(&infos), 2006); // ConstraintSeeds
+    }
+
+    #[test]
+    fn test_draw_winners_after_pool_bid_withdrawn() {
+        set_syscall_stubs(Box::new(InitStubs));
+        let organizer = test_pubkey(30);
+        let event_key = event_address(&organizer).0;
+        let randomness_key = test_pubkey(31);
+        let mut event = Event {
+            status: EVENT_STATUS_ACTIVE,
+            lottery_randomness: randomness_key,
+            ticket_supply: 1,
+            tiers: vec![TicketTier { supply: 1, end_price: 100_000_000, ..Default::default() }],
+            ..test_event(organizer)
+        };
+        
+        // Two bids enter the lottery pool, then one is withdrawn before the draw
+        let mut kept = Bid { amount: 100_000_000, ..test_bid(event_key, test_pubkey(32)) };
+        kept.open(&mut event, 1).unwrap();
+        let mut withdrawn = Bid { amount: 100_000_000, ..test_bid(event_key, test_pubkey(33)) };
+        withdrawn.open(&mut event, 1).unwrap();
+        withdrawn.withdraw(&mut event, 0).unwrap();
+        assert_eq!(event.lottery_pool_bids, 1);
+        
+        // Randomness revealed in the current slot
+        let mut randomness = vec![0; SwitchboardRandomness::LEN];
+        randomness[..8].copy_from_slice(&SwitchboardRandomness::discriminator());
+        randomness[152..184].copy_from_slice(&[42u8; 32]);
+        let mut accounts = [
+            TestAccount::signer(organizer),
+            TestAccount::program_owned(event_key, &event),
+            TestAccount { data: randomness, ..TestAccount::new(randomness_key, SWITCHBOARD_ON_DEMAND_PROGRAM_ID) },
+            TestAccount::program_owned(bid_address(&event_key, &kept.bidder).0, &kept),
+        ];
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        let (named, remaining) = infos.split_at(3);
+        
+        // The draw runs with just the bid left in the pool, which wins the one ticket
+        let mut constraints = validate::<DrawWinnersAccountConstraints, _>(named).unwrap();
+        draw_winners(Context::new(&escrow::ID, &mut constraints, remaining, Default::default())).unwrap();
+        let drawn = Bid::try_deserialize(&mut &remaining[0].try_borrow_data().unwrap()[..]).unwrap();
+        assert!(drawn.lottery_winner);
+    }
+
+    #[test]
+    fn test_rank_sealed_bids_after_withdrawal_attempt() {
+        set_syscall_stubs(Box::new(InitStubs));
+        let organizer = test_pubkey(40);
+        let event_key = event_address(&organizer).0;
+        
+        // The reveal window has closed with one bid revealed
+        let mut event = Event {
+            status: EVENT_STATUS_ACTIVE,
+            auction_type: AUCTION_TYPE_SEALED,
+            auction_start_time: -300,
+            auction_end_time: -200,
+            extended_end_time: -200,
+            reveal_end_time: -100,
+            ticket_supply: 1,
+            sealed_bids_revealed: 1,
+            ..test_event(organizer)
+        };
+        let mut revealed = test_bid(event_key, test_pubkey(41));
+        
+        // The revealed bid can't be withdrawn before ranking, so it's still there to be ranked
+        assert_rejected(revealed.withdraw(&mut event, 0), escrow::error::ErrorCode::BidNotWithdrawable.into());
+        let mut accounts = [
+            TestAccount::signer(organizer),
+            TestAccount::program_owned(event_key, &event),
+            TestAccount::program_owned(bid_address(&event_key, &revealed.bidder).0, &revealed),
+        ];
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        let (named, remaining) = infos.split_at(2);
+        let mut constraints = validate::<RankSealedBidsAccountConstraints, _>(named).unwrap();
+        rank_sealed_bids(Context::new(&escrow::ID, &mut constraints, remaining, Default::default())).unwrap();
+        let ranked = Bid::try_deserialize(&mut &remaining[0].try_borrow_data().unwrap()[..]).unwrap();
+        assert!(ranked.sealed_winner);
+    }
+
+    #[test]
+    fn test_release_ticket_returns_it_to_tier_with_nobody_waiting() {
+        set_syscall_stubs(Box::new(InitStubs));
+        let organizer = test_pubkey(50);
+        let holder = test_pubkey(51);
+        let event_key = event_address(&organizer).0;
+        let event = Event {
+            status: EVENT_STATUS_ACTIVE,
+            ticket_supply: 1,
+            tickets_awarded: 1,
+            assets_awarded: 1,
+            assets_registered: 1,
+            tiers: vec![TicketTier { supply: 1, awarded: 1, ..Default::default() }],
+            ..test_event(organizer)
+        };
+        let (ticket_key, ticket) = test_ticket(event_key, holder, holder);
+        let bid = Bid { status: BID_STATUS_AWARDED, quantity_awarded: 1, ..test_bid(event_key, holder) };
+        
+        // The holder returns their ticket to a sold-out tier with an empty waitlist, so no bid or
+        // ticket is passed for a promoted bidder
+        let mut accounts = vec![
+            TestAccount::signer(holder),
+            TestAccount::program_owned(event_key, &event),
+            TestAccount::program_owned(ticket_key, &ticket),
+            TestAccount::program_owned(bid_address(&event_key, &holder).0, &bid),
+            TestAccount::new(holder, system_program::ID),
+            TestAccount::omitted(),
+            TestAccount::omitted(),
+            TestAccount::omitted(),
+            TestAccount::omitted(),
+            TestAccount::new(escrow_address(&event_key), system_program::ID),
+        ];
+        accounts.extend((0..4).map(|_| TestAccount::omitted()));
+        accounts.push(TestAccount::system_program());
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        let mut constraints = validate::<ReleaseTicketAccountConstraints, _>(&infos).unwrap();
+        release_ticket(Context::new(&escrow::ID, &mut constraints, &[], Default::default())).unwrap();
+        
+        // The tier can award the ticket again, once a new asset is registered for it
+        assert_eq!(constraints.event.tiers[0].awarded, 0);
+        assert_eq!(constraints.event.tickets_awarded, 0);
+        assert_eq!(constraints.event.assets_awarded, 1);
+        assert_eq!(constraints.event.asset_capacity(), 2);
+        assert_eq!(constraints.ticket.status, TICKET_STATUS_REFUNDED);
+        assert_eq!(constraints.bid.status, BID_STATUS_REFUNDED);
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(unused_imports, unused_mut, unused_variables, clippy::implicit_saturating_sub)]
+
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::system_program;
 use anchor_lang::prelude::Pubkey;
@@ -62,7 +64,7 @@ mod tests {
             status: EVENT_STATUS_CREATED,
             bump: 255,
             merkle_tree,
-            cnft_asset_ids: vec![],
+            ..Default::default()
         };
 
         // Assert event fields
@@ -76,8 +78,8 @@ mod tests {
         assert_eq!(event.auction_end_time, auction_end_time);
         assert_eq!(event.status, EVENT_STATUS_CREATED);
         assert_eq!(event.merkle_tree, merkle_tree);
-        // Asset IDs should be empty at creation
-        assert!(event.cnft_asset_ids.is_empty());
+        // Asset IDs live in inventory pages, one page per TICKETS_PER_INVENTORY_PAGE tickets
+        assert_eq!(state::TicketInventoryPage::page_count(event.ticket_supply), 1);
     }
 
     #[test]
@@ -96,7 +98,7 @@ mod tests {
             status: EVENT_STATUS_CREATED,
             bump: 255,
             merkle_tree: test_pubkey(2),
-            cnft_asset_ids: vec![],
+            ..Default::default()
         };
 
         // Test activation
@@ -117,6 +119,7 @@ mod tests {
             amount,
             status: BID_STATUS_PENDING,
             bump: 254,
+            ..Default::default()
         };
         // Assert bid fields
         assert_eq!(bid.bidder, bidder);
@@ -142,6 +145,7 @@ mod tests {
             offchain_ref: String::new(),
             bump: 253,
             cnft_asset_id,
+            ..Default::default()
         };
         // Assert ticket fields
         assert_eq!(ticket.owner, owner);
@@ -152,6 +156,12 @@ mod tests {
         // Test helper methods
         assert!(ticket.can_claim());
         assert!(ticket.can_refund());
+        
+        // A ticket that's been passed on can't be released against the original bid
+        ticket.original_owner = owner;
+        assert!(!ticket.has_changed_hands());
+        ticket.owner = test_pubkey(8);
+        assert!(ticket.has_changed_hands());
     }
 
     #[test]
@@ -170,7 +180,7 @@ mod tests {
             status: EVENT_STATUS_ACTIVE,
             bump: 255,
             merkle_tree: test_pubkey(2),
-            cnft_asset_ids: vec![],
+            ..Default::default()
         };
         
         // Test finalization condition
@@ -208,7 +218,7 @@ mod tests {
             status: EVENT_STATUS_ACTIVE,
             bump: 255,
             merkle_tree: test_pubkey(2),
-            cnft_asset_ids: vec![],
+            ..Default::default()
         };
         
         // Test pricing at different times
@@ -241,6 +251,7 @@ mod tests {
             amount: 2_000_000,
             status: BID_STATUS_PENDING, // Pending
             bump: 252,
+            ..Default::default()
         };
         // Refund logic: losing bid
         bid.status = BID_STATUS_REFUNDED; // Refunded
@@ -261,7 +272,7 @@ mod tests {
             status: EVENT_STATUS_FINALIZED,
             bump: 251,
             merkle_tree: test_pubkey(11),
-            cnft_asset_ids: vec![],
+            ..Default::default()
         };
 
         // Simulate a partial refund for a winning bid (overbid)
@@ -271,6 +282,7 @@ mod tests {
             amount: 2_000_000, // Bid was at this higher amount
             status: BID_STATUS_AWARDED, // Awarded
             bump: 250,
+            ..Default::default()
         };
         
         // Calculate expected refund amount
@@ -285,7 +297,7 @@ mod tests {
     #[test]
     fn test_bubblegum_cnft_logic() {
         // Simulate cNFT minting, transfer, and burn logic
-        let mut event = state::Event {
+        let event = state::Event {
             organizer: test_pubkey(12),
             metadata_url: "https://example.com/event.json".to_string(),
             ticket_supply: 2,
@@ -298,14 +310,21 @@ mod tests {
             status: EVENT_STATUS_CREATED,
             bump: 250,
             merkle_tree: test_pubkey(13),
-            cnft_asset_ids: vec![],
+            ..Default::default()
+        };
+        // Mint cNFTs (simulate by writing asset IDs into the inventory page)
+        let mut inventory_page = state::TicketInventoryPage {
+            event: test_pubkey(12),
+            page_index: 0,
+            asset_count: 0,
+            asset_ids: [Pubkey::default(); TICKETS_PER_INVENTORY_PAGE],
         };
-        // Mint cNFTs (simulate by pushing asset IDs)
         let asset_id1 = test_pubkey(14);
         let asset_id2 = test_pubkey(15);
-        event.cnft_asset_ids.push(asset_id1);
-        event.cnft_asset_ids.push(asset_id2);
-        assert_eq!(event.cnft_asset_ids.len(), 2);
+        inventory_page.asset_ids[0] = asset_id1;
+        inventory_page.asset_ids[1] = asset_id2;
+        inventory_page.asset_count = 2;
+        assert_eq!(inventory_page.asset_count, event.ticket_supply);
         // Transfer cNFT (simulate by removing from event and assigning to ticket)
         let mut ticket = state::Ticket {
             owner: test_pubkey(16),
@@ -314,9 +333,1305 @@ mod tests {
             offchain_ref: String::new(),
             bump: 249,
             cnft_asset_id: asset_id1,
+            ..Default::default()
+        };
+        // Burn unsold cNFT (simulate by clearing it from the inventory)
+        inventory_page.asset_ids[1] = Pubkey::default();
+        assert!(inventory_page.asset_ids.iter().all(|&id| id != asset_id2));
+    }
+
+    #[test]
+    fn test_minimum_sales_threshold() {
+        // Simulate an event that needs at least 5 tickets sold to go ahead, where only 3 were awarded
+        let under_sold_event = state::Event {
+            organizer: test_pubkey(17),
+            metadata_url: "https://example.com/event.json".to_string(),
+            ticket_supply: 10,
+            tickets_awarded: 3,
+            min_tickets_sold: 5,
+            start_price: 1_000_000,
+            end_price: 100_000,
+            auction_start_time: test_time() - 7200,
+            auction_end_time: test_time() - 3600,
+            auction_close_price: 0,
+            status: EVENT_STATUS_ACTIVE,
+            bump: 255,
+            merkle_tree: test_pubkey(18),
+            tiers: vec![state::TicketTier {
+                supply: 10,
+                awarded: 3,
+                start_price: 1_000_000,
+                end_price: 100_000,
+                lowest_awarded_bid: 800_000,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let awarded_bid = state::Bid {
+            bidder: test_pubkey(19),
+            event: test_pubkey(17),
+            amount: 800_000,
+            status: BID_STATUS_AWARDED,
+            bump: 248,
+            quantity: 3,
+            quantity_awarded: 3,
+            ..Default::default()
+        };
+        
+        // Below the threshold finalizing fails the event instead of setting a close price
+        let mut event = under_sold_event.clone();
+        event.finalize(500_000, vec![], test_time()).unwrap();
+        assert_eq!(event.status, EVENT_STATUS_FAILED);
+        assert_eq!(event.auction_close_price, 0);
+        assert!(event.finalize(500_000, vec![], test_time()).is_err()); // Only once
+        
+        // Awarded bids on a failed event are refunded in full, exactly once
+        let mut refunded_bid = awarded_bid.clone();
+        assert_eq!(refunded_bid.settle(&event).unwrap(), 2_400_000);
+        assert_eq!(refunded_bid.status, BID_STATUS_REFUNDED);
+        assert!(refunded_bid.settle(&event).is_err());
+        
+        // Reaching the threshold lets the event go ahead, and winners only get back what they overpaid
+        let mut event = state::Event { tickets_awarded: 5, ..under_sold_event.clone() };
+        event.finalize(500_000, vec![], test_time()).unwrap();
+        assert_eq!(event.status, EVENT_STATUS_FINALIZED);
+        let mut winning_bid = awarded_bid.clone();
+        assert_eq!(winning_bid.settle(&event).unwrap(), 900_000);
+        
+        // No minimum configured always goes ahead
+        let mut event = state::Event { min_tickets_sold: 0, ..under_sold_event };
+        event.finalize(500_000, vec![], test_time()).unwrap();
+        assert_eq!(event.status, EVENT_STATUS_FINALIZED);
+    }
+
+    #[test]
+    fn test_token_denominated_event() {
+        // Simulate an event priced in a 6-decimal stablecoin
+        let payment_mint = test_pubkey(20);
+        let mut event = state::Event {
+            organizer: test_pubkey(21),
+            metadata_url: "https://example.com/event.json".to_string(),
+            ticket_supply: 10,
+            tickets_awarded: 4,
+            start_price: 50_000_000, // 50 USDC
+            end_price: 20_000_000, // 20 USDC
+            auction_start_time: test_time() - 7200,
+            auction_end_time: test_time() - 3600,
+            auction_close_price: 0,
+            payment_mint,
+            status: EVENT_STATUS_ACTIVE,
+            bump: 255,
+            merkle_tree: test_pubkey(22),
+            ..Default::default()
+        };
+        assert!(event.is_token_denominated());
+        
+        // Pricing works in the mint's base units
+        assert_eq!(event.get_current_auction_price(test_time()), 20_000_000);
+        
+        // Proceeds are tickets awarded at the close price
+        event.tiers = vec![state::TicketTier {
+            supply: 10,
+            awarded: 4,
+            start_price: 50_000_000,
+            end_price: 20_000_000,
+            close_price: 25_000_000,
+            ..Default::default()
+        }];
+        event.auction_close_price = 25_000_000;
+        event.status = EVENT_STATUS_FINALIZED;
+        assert_eq!(event.proceeds(), Some(100_000_000));
+        assert!(!event.proceeds_withdrawn);
+        
+        // SOL events use the default pubkey as the payment mint
+        event.payment_mint = Pubkey::default();
+        assert!(!event.is_token_denominated());
+    }
+
+    #[test]
+    fn test_multi_quantity_bids() {
+        // Simulate a family bidding for 4 tickets at 2 SOL each
+        let mut bid = state::Bid {
+            bidder: test_pubkey(23),
+            event: test_pubkey(24),
+            amount: 2_000_000_000,
+            status: BID_STATUS_PENDING,
+            bump: 247,
+            quantity: 4,
+            quantity_awarded: 0,
+            ..Default::default()
+        };
+        assert!(bid.quantity <= MAX_TICKETS_PER_BID);
+        assert_eq!(bid.total_amount(), Some(8_000_000_000));
+        
+        // Only 3 of the 4 tickets could be awarded
+        bid.status = BID_STATUS_AWARDED;
+        bid.quantity_awarded = 3;
+        
+        // Auction clears at 1.5 SOL: 0.5 SOL back per awarded ticket, 2 SOL back for the unawarded one
+        let close_price = 1_500_000_000;
+        assert_eq!(bid.refund_due(close_price), Some(3 * 500_000_000 + 2_000_000_000));
+        
+        // All tickets awarded at the bid price: nothing to refund
+        bid.quantity_awarded = 4;
+        assert_eq!(bid.refund_due(bid.amount), Some(0));
+    }
+
+    #[test]
+    fn test_per_wallet_ticket_cap() {
+        // Simulate an event capped at 4 tickets per wallet
+        let event_key = test_pubkey(25);
+        let event = state::Event {
+            organizer: test_pubkey(26),
+            ticket_supply: 10,
+            max_tickets_per_wallet: 4,
+            status: EVENT_STATUS_ACTIVE,
+            ..Default::default()
+        };
+        let mut user = state::User {
+            authority: test_pubkey(27),
+            ..Default::default()
+        };
+        
+        // First purchase of 3 tickets fits under the cap
+        assert_eq!(user.tickets_held(&event_key), 0);
+        assert!(event.within_wallet_cap(user.tickets_held(&event_key), 3));
+        user.record_tickets(&event_key, 3).unwrap();
+        assert_eq!(user.tickets_held(&event_key), 3);
+        assert_eq!(user.tickets_purchased, 3);
+        
+        // One more fits, two more would exceed the cap
+        assert!(event.within_wallet_cap(user.tickets_held(&event_key), 1));
+        assert!(!event.within_wallet_cap(user.tickets_held(&event_key), 2));
+        
+        // Holdings are tracked per event
+        let other_event = test_pubkey(28);
+        user.record_tickets(&other_event, 2).unwrap();
+        assert_eq!(user.tickets_held(&event_key), 3);
+        assert_eq!(user.tickets_held(&other_event), 2);
+        assert_eq!(user.tickets_purchased, 5);
+        
+        // Holdings list is bounded
+        for seed in 0..(MAX_EVENT_HOLDINGS_PER_USER - 2) {
+            user.record_tickets(&Pubkey::new_from_array([100 + seed as u8; 32]), 1).unwrap();
+        }
+        assert!(user.record_tickets(&test_pubkey(29), 1).is_err());
+        
+        // No cap configured
+        let uncapped = state::Event { max_tickets_per_wallet: 0, ..Default::default() };
+        assert!(uncapped.within_wallet_cap(1000, 1000));
+    }
+
+    #[test]
+    fn test_ticket_tiers() {
+        // Simulate an event with GA and VIP tiers on the same auction window
+        let start_time = test_time();
+        let end_time = start_time + 3600;
+        let mut event = state::Event {
+            organizer: test_pubkey(30),
+            ticket_supply: 12,
+            start_price: 1_000_000,
+            end_price: 100_000,
+            auction_start_time: start_time,
+            auction_end_time: end_time,
+            status: EVENT_STATUS_ACTIVE,
+            tiers: vec![
+                state::TicketTier {
+                    supply: 10,
+                    start_price: 1_000_000,
+                    end_price: 100_000,
+                    ..Default::default()
+                },
+                state::TicketTier {
+                    supply: 2,
+                    start_price: 5_000_000,
+                    end_price: 3_000_000,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        
+        // Each tier follows its own price curve
+        let halfway_time = start_time + 1800;
+        assert_eq!(event.get_tier_price(0, halfway_time), Some(event.get_current_auction_price(halfway_time)));
+        assert_eq!(event.get_tier_price(1, halfway_time), Some(4_000_000));
+        assert_eq!(event.get_tier_price(2, halfway_time), None); // No such tier
+        
+        // Awarding tracks supply per tier
+        event.tiers[0].awarded = 6;
+        event.tiers[1].awarded = 2;
+        event.tickets_awarded = 8;
+        assert_eq!(event.tiers[1].awarded, event.tiers[1].supply); // VIP sold out
+        
+        // Each tier closes at its own price, and refunds use the bid's tier
+        event.tiers[0].close_price = 400_000;
+        event.tiers[1].close_price = 3_500_000;
+        let vip_bid = state::Bid {
+            bidder: test_pubkey(31),
+            event: test_pubkey(30),
+            amount: 4_000_000,
+            status: BID_STATUS_AWARDED,
+            quantity: 2,
+            quantity_awarded: 2,
+            tier: 1,
+            ..Default::default()
+        };
+        let close_price = event.tier_close_price(vip_bid.tier).unwrap();
+        assert_eq!(vip_bid.refund_due(close_price), Some(1_000_000));
+        
+        // Proceeds add up across tiers
+        assert_eq!(event.proceeds(), Some(6 * 400_000 + 2 * 3_500_000));
+    }
+
+    #[test]
+    fn test_event_parameter_validation() {
+        // Simulate a valid event awaiting activation
+        let mut event = state::Event {
+            organizer: test_pubkey(32),
+            metadata_url: "https://example.com/event.json".to_string(),
+            ticket_supply: 10,
+            start_price: 1_000_000,
+            end_price: 1_000_000,
+            auction_start_time: test_time(),
+            auction_end_time: test_time() + 3600,
+            status: EVENT_STATUS_CREATED,
+            tiers: vec![state::TicketTier {
+                supply: 10,
+                start_price: 1_000_000,
+                end_price: 1_000_000,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(event.validate_params().is_ok());
+        
+        // Metadata URL length is enforced
+        event.metadata_url = "x".repeat(MAX_METADATA_URL_LEN + 1);
+        assert!(event.validate_params().is_err());
+        event.metadata_url = "x".repeat(MAX_METADATA_URL_LEN);
+        assert!(event.validate_params().is_ok());
+        
+        // Auction must last at least the minimum duration
+        event.auction_end_time = event.auction_start_time + MIN_AUCTION_DURATION - 1;
+        assert!(event.validate_params().is_err());
+        event.auction_end_time = event.auction_start_time + MIN_AUCTION_DURATION;
+        
+        // Prices below the minimum are rejected, for every tier
+        event.tiers[0].end_price = MIN_TICKET_PRICE - 1;
+        assert!(event.validate_params().is_err());
+        event.tiers[0].end_price = MIN_TICKET_PRICE;
+        
+        // Minimum sales can't exceed supply
+        event.min_tickets_sold = 11;
+        assert!(event.validate_params().is_err());
+        event.min_tickets_sold = 0;
+        
+        // Supply can't be cut below the tickets already registered as assets or awarded
+        event.assets_registered = 8;
+        event.ticket_supply = 7;
+        event.tiers[0].supply = 7;
+        assert!(event.validate_params().is_err());
+        event.assets_registered = 0;
+        assert!(event.validate_params().is_ok());
+        event.tickets_awarded = 8;
+        event.tiers[0].awarded = 8;
+        assert!(event.validate_params().is_err());
+        event.tickets_awarded = 0;
+        event.tiers[0].awarded = 0;
+        event.ticket_supply = 10;
+        event.tiers[0].supply = 10;
+        
+        // Changing the base tier supply keeps other tiers' supply in the total
+        event.tiers.push(state::TicketTier { supply: 5, ..Default::default() });
+        event.ticket_supply = 15;
+        assert_eq!(event.supply_with_base_tier(20), 25);
+    }
+
+    #[test]
+    fn test_auto_activation() {
+        // Simulate an auto-activating event whose organizer never calls activate_event
+        let mut event = state::Event {
+            organizer: test_pubkey(33),
+            ticket_supply: 10,
+            start_price: 1_000_000,
+            end_price: 100_000,
+            auction_start_time: test_time(),
+            auction_end_time: test_time() + 3600,
+            auto_activate: true,
+            status: EVENT_STATUS_CREATED,
+            ..Default::default()
+        };
+        
+        // Not due before the auction starts
+        assert!(!event.is_due_for_activation(test_time() - 1));
+        assert!(!event.activate_if_due(test_time() - 1));
+        assert_eq!(event.status, EVENT_STATUS_CREATED);
+        
+        // Due once the auction start time is reached, and becomes biddable
+        assert!(event.activate_if_due(test_time()));
+        assert_eq!(event.status, EVENT_STATUS_ACTIVE);
+        assert!(event.is_active_for_bidding(test_time()));
+        
+        // Already active: nothing more to do
+        assert!(!event.activate_if_due(test_time() + 1));
+        
+        // Events that didn't opt in still need the organizer
+        let manual_event = state::Event {
+            auction_start_time: test_time(),
+            auto_activate: false,
+            status: EVENT_STATUS_CREATED,
+            ..Default::default()
+        };
+        assert!(!manual_event.is_due_for_activation(test_time() + 3600));
+    }
+
+    #[test]
+    fn test_anti_sniping_extension() {
+        // 1-hour auction: bids in the last 60s add 120s, up to 300s in total
+        let start_time = test_time();
+        let end_time = start_time + 3600;
+        let mut event = state::Event {
+            organizer: test_pubkey(34),
+            ticket_supply: 10,
+            start_price: 1_000_000,
+            end_price: 100_000,
+            auction_start_time: start_time,
+            auction_end_time: end_time,
+            extended_end_time: end_time,
+            extension_window: 60,
+            extension_duration: 120,
+            max_extension: 300,
+            status: EVENT_STATUS_ACTIVE,
+            ..Default::default()
+        };
+        
+        // A bid well before the end doesn't extend the auction
+        assert!(!event.extend_for_bid(end_time - 61));
+        assert_eq!(event.effective_end_time(), end_time);
+        
+        // A late bid extends it, and the original end time is kept
+        assert!(event.extend_for_bid(end_time - 10));
+        assert_eq!(event.auction_end_time, end_time);
+        assert_eq!(event.effective_end_time(), end_time + 120);
+        assert!(event.is_active_for_bidding(end_time + 60));
+        assert!(!event.can_finalize(end_time + 60));
+        
+        // Pricing follows the effective end
+        assert!(event.get_current_auction_price(end_time) > event.end_price);
+        assert_eq!(event.get_current_auction_price(end_time + 120), event.end_price);
+        
+        // Further extensions are capped at 300s past the original end
+        assert!(event.extend_for_bid(end_time + 100));
+        assert_eq!(event.effective_end_time(), end_time + 240);
+        assert!(event.extend_for_bid(end_time + 200));
+        assert_eq!(event.effective_end_time(), end_time + 300);
+        assert!(!event.extend_for_bid(end_time + 290));
+        assert_eq!(event.effective_end_time(), end_time + 300);
+        assert!(event.can_finalize(end_time + 300));
+        
+        // Events without a configured extension never extend
+        let mut plain_event = state::Event {
+            auction_end_time: end_time,
+            ..Default::default()
+        };
+        assert!(!plain_event.extend_for_bid(end_time - 1));
+    }
+
+    #[test]
+    fn test_paginated_ticket_inventory() {
+        // Large events are split across fixed-size inventory pages
+        assert_eq!(state::TicketInventoryPage::page_count(0), 0);
+        assert_eq!(state::TicketInventoryPage::page_count(10), 1);
+        assert_eq!(state::TicketInventoryPage::page_count(TICKETS_PER_INVENTORY_PAGE as u32), 1);
+        assert_eq!(state::TicketInventoryPage::page_count(MAX_TICKETS_PER_EVENT), 4);
+        
+        // Ticket indexes map to a page and a slot within it
+        assert_eq!(state::TicketInventoryPage::locate(0), (0, 0));
+        assert_eq!(state::TicketInventoryPage::locate(255), (0, 255));
+        assert_eq!(state::TicketInventoryPage::locate(256), (1, 0));
+        assert_eq!(state::TicketInventoryPage::locate(999), (3, 231));
+        
+        // Each page stays under the 10KB limit for accounts created via CPI
+        const { assert!(8 + state::TicketInventoryPage::INIT_SPACE <= 10_240) };
+        assert_eq!(std::mem::size_of::<state::TicketInventoryPage>(), state::TicketInventoryPage::INIT_SPACE);
+        
+        // The event account no longer grows with ticket supply
+        const { assert!(8 + state::Event::INIT_SPACE <= 10_240) };
+    }
+    
+    #[test]
+    fn test_register_ticket_assets() {
+        let mut inventory_page = state::TicketInventoryPage {
+            event: test_pubkey(1),
+            page_index: 0,
+            asset_count: 0,
+            asset_ids: [Pubkey::default(); TICKETS_PER_INVENTORY_PAGE],
+        };
+        let first_chunk: Vec<Pubkey> = (10..20).map(test_pubkey).collect();
+        let second_chunk: Vec<Pubkey> = (20..25).map(test_pubkey).collect();
+        
+        // Chunks fill the page in order
+        assert!(inventory_page.register_assets(0, &first_chunk).is_ok());
+        assert_eq!(inventory_page.asset_count, 10);
+        assert_eq!(inventory_page.asset_at(0), Some(test_pubkey(10)));
+        assert_eq!(inventory_page.asset_at(9), Some(test_pubkey(19)));
+        assert_eq!(inventory_page.asset_at(10), None);
+        
+        // Replaying or skipping ahead is rejected
+        assert!(inventory_page.register_assets(0, &first_chunk).is_err());
+        assert!(inventory_page.register_assets(11, &second_chunk).is_err());
+        assert!(inventory_page.register_assets(10, &second_chunk).is_ok());
+        assert_eq!(inventory_page.asset_at(14), Some(test_pubkey(24)));
+        
+        // A chunk can't spill past the end of the page
+        inventory_page.asset_count = (TICKETS_PER_INVENTORY_PAGE - 2) as u32;
+        assert!(inventory_page.register_assets(TICKETS_PER_INVENTORY_PAGE - 2, &second_chunk).is_err());
+        assert!(inventory_page.register_assets(TICKETS_PER_INVENTORY_PAGE - 2, &second_chunk[..2]).is_ok());
+        assert_eq!(inventory_page.asset_count, TICKETS_PER_INVENTORY_PAGE as u32);
+    }
+    
+    #[test]
+    fn test_payee_revenue_split() {
+        // Promoter 50%, venue 30%, artist 20%, with the artist taking rounding dust
+        let payees = vec![
+            state::Payee { recipient: test_pubkey(1), share_bps: 5_000 },
+            state::Payee { recipient: test_pubkey(2), share_bps: 3_000 },
+            state::Payee { recipient: test_pubkey(3), share_bps: 2_000 },
+        ];
+        assert!(state::Event::validate_payees(&payees, 2).is_ok());
+        
+        // Shares must add up to 100% and the dust payee must be in the table
+        assert!(state::Event::validate_payees(&payees[..2], 0).is_err());
+        assert!(state::Event::validate_payees(&payees, 3).is_err());
+        assert!(state::Event::validate_payees(&[], 0).is_err());
+        let duplicated = vec![
+            state::Payee { recipient: test_pubkey(1), share_bps: 5_000 },
+            state::Payee { recipient: test_pubkey(1), share_bps: 5_000 },
+        ];
+        assert!(state::Event::validate_payees(&duplicated, 0).is_err());
+        
+        let event = state::Event {
+            payees,
+            dust_payee: 2,
+            ..Default::default()
+        };
+        
+        // Even splits leave no dust
+        assert_eq!(event.payee_amounts(1_000_000), vec![500_000, 300_000, 200_000]);
+        
+        // Rounding dust goes to the dust payee and nothing is lost
+        let amounts = event.payee_amounts(1_001);
+        assert_eq!(amounts, vec![500, 300, 201]);
+        assert_eq!(amounts.iter().sum::<u64>(), 1_001);
+    }
+    
+    #[test]
+    fn test_rent_reclamation() {
+        // An awarded bid can only be settled once, after which its account can be closed
+        let mut bid = state::Bid {
+            amount: 800_000,
+            status: BID_STATUS_AWARDED,
+            quantity: 1,
+            quantity_awarded: 1,
+            ..Default::default()
+        };
+        assert!(bid.can_refund());
+        assert!(!bid.can_close());
+        bid.settled = true;
+        assert!(!bid.can_refund());
+        assert!(bid.can_close());
+        
+        // A finalized event can't be closed while bids are open or proceeds are unpaid
+        let mut event = state::Event {
+            status: EVENT_STATUS_FINALIZED,
+            tickets_awarded: 1,
+            bids_open: 2,
+            auction_close_price: 500_000,
+            tiers: vec![state::TicketTier { supply: 10, awarded: 1, close_price: 500_000, ..Default::default() }],
+            ..Default::default()
+        };
+        assert!(!event.is_fully_settled());
+        event.bids_open = 0;
+        assert!(!event.is_fully_settled());
+        event.proceeds_withdrawn = true;
+        assert!(event.is_fully_settled());
+        
+        // Live auctions are never closable; failed ones are once every bid is closed
+        event.status = EVENT_STATUS_ACTIVE;
+        assert!(!event.is_fully_settled());
+        event.status = EVENT_STATUS_FAILED;
+        assert!(event.is_fully_settled());
+    }
+    
+    #[test]
+    fn test_uniform_price_settlement() {
+        let event = state::Event {
+            status: EVENT_STATUS_FINALIZED,
+            uniform_price: true,
+            auction_close_price: 600_000,
+            tiers: vec![state::TicketTier { supply: 10, awarded: 2, close_price: 600_000, ..Default::default() }],
+            ..Default::default()
+        };
+        
+        // A winner who bid above the clearing price gets the difference back, exactly once
+        let mut winning_bid = state::Bid {
+            amount: 900_000,
+            status: BID_STATUS_AWARDED,
+            quantity: 2,
+            quantity_awarded: 2,
+            ..Default::default()
+        };
+        assert_eq!(winning_bid.settle(&event).unwrap(), 600_000);
+        assert!(winning_bid.settled);
+        assert_eq!(winning_bid.status, BID_STATUS_AWARDED);
+        assert!(winning_bid.settle(&event).is_err());
+        
+        // A losing bid gets everything back
+        let mut losing_bid = state::Bid {
+            amount: 700_000,
+            status: BID_STATUS_PENDING,
+            quantity: 1,
+            ..Default::default()
+        };
+        assert_eq!(losing_bid.settle(&event).unwrap(), 700_000);
+        assert_eq!(losing_bid.status, BID_STATUS_REFUNDED);
+        assert!(losing_bid.settle(&event).is_err());
+        
+        // Winners can't be settled before the clearing price is known
+        let live_event = state::Event {
+            status: EVENT_STATUS_ACTIVE,
+            ..Default::default()
         };
-        // Burn unsold cNFT (simulate by removing from event)
-        event.cnft_asset_ids.retain(|&id| id != asset_id2);
-        assert_eq!(event.cnft_asset_ids.len(), 1);
+        let mut early_bid = state::Bid {
+            amount: 900_000,
+            status: BID_STATUS_AWARDED,
+            quantity: 1,
+            quantity_awarded: 1,
+            ..Default::default()
+        };
+        assert!(early_bid.settle(&live_event).is_err());
+        assert!(!early_bid.settled);
+    }
+    
+    #[test]
+    fn test_sealed_bid_commit_reveal() {
+        let start = test_time();
+        let mut event = state::Event {
+            metadata_url: "https://example.com/event.json".to_string(),
+            ticket_supply: 10,
+            start_price: 1_000_000_000,
+            end_price: 100_000_000,
+            auction_start_time: start,
+            auction_end_time: start + 3600,
+            extended_end_time: start + 3600,
+            status: EVENT_STATUS_ACTIVE,
+            auction_type: AUCTION_TYPE_SEALED,
+            reveal_end_time: start + 7200,
+            sealed_deposit: 200_000_000,
+            unrevealed_slash_bps: 2_500,
+            tiers: vec![state::TicketTier { supply: 10, start_price: 1_000_000_000, end_price: 100_000_000, ..Default::default() }],
+            ..Default::default()
+        };
+        assert!(event.validate_params().is_ok());
+        
+        // Commit window, then reveal window, then closed
+        assert_eq!(event.auction_phase(start - 1), AUCTION_PHASE_NOT_STARTED);
+        assert_eq!(event.auction_phase(start + 100), AUCTION_PHASE_COMMIT);
+        assert_eq!(event.auction_phase(start + 3601), AUCTION_PHASE_REVEAL);
+        assert_eq!(event.auction_phase(start + 7201), AUCTION_PHASE_CLOSED);
+        assert!(!event.can_finalize(start + 3601));
+        assert!(event.can_finalize(start + 7201));
+        
+        // Only the committed amount and salt open the commitment
+        let salt = [7u8; 32];
+        let commitment = state::Bid::sealed_commitment(750_000, &salt);
+        assert_eq!(commitment, state::Bid::sealed_commitment(750_000, &salt));
+        assert_ne!(commitment, state::Bid::sealed_commitment(750_001, &salt));
+        assert_ne!(commitment, state::Bid::sealed_commitment(750_000, &[8u8; 32]));
+        
+        // Unrevealed deposits are refunded less the slash once the auction is finalized
+        let mut unrevealed_bid = state::Bid {
+            amount: event.sealed_deposit,
+            status: BID_STATUS_COMMITTED,
+            quantity: 2,
+            commitment,
+            ..Default::default()
+        };
+        assert!(!unrevealed_bid.can_award());
+        assert!(unrevealed_bid.settle(&event).is_err());
+        event.unrevealed_slashes = event.unrevealed_slash(unrevealed_bid.total_amount().unwrap());
+        event.status = EVENT_STATUS_FINALIZED;
+        assert_eq!(unrevealed_bid.settle(&event).unwrap(), 300_000_000);
+        assert_eq!(unrevealed_bid.status, BID_STATUS_REFUNDED);
+        
+        // The slashed part of the deposit is paid out with the proceeds, so it goes through any payee split
+        assert_eq!(event.proceeds(), Some(100_000_000));
+        assert!(!event.is_fully_settled());
+        event.proceeds_withdrawn = true;
+        assert!(event.is_fully_settled());
+        
+        // Issued tickets outlive a closed event, but tickets listed for resale keep it open
+        event.tickets_awarded = 1;
+        assert!(!event.has_open_listings());
+        event.listings_open = 1;
+        assert!(event.has_open_listings());
+        event.tickets_awarded = 0;
+        event.listings_open = 0;
+        
+        // Sealed-bid auctions need a reveal window and a single tier
+        event.reveal_end_time = event.auction_end_time;
+        assert!(event.validate_params().is_err());
+        event.reveal_end_time = start + 7200;
+        event.tiers.push(state::TicketTier { supply: 5, start_price: 500_000_000, end_price: 100_000_000, ..Default::default() });
+        assert!(event.validate_params().is_err());
+    }
+    
+    #[test]
+    fn test_lottery_draw() {
+        use state::RandomnessProvider;
+        
+        // Local stand-in for a Switchboard randomness account
+        let randomness = state::LocalRandomness { seed_slot: 100, reveal_slot: 0, value: [42u8; 32] };
+        assert!(!randomness.is_revealed());
+        assert!(randomness.reveal_value(101).is_err());
+        let randomness = state::LocalRandomness { reveal_slot: 102, ..randomness };
+        let random_value = randomness.reveal_value(102).unwrap();
+        assert!(randomness.reveal_value(103).is_err());
+        
+        // Six single-ticket bids at the end price compete for four tickets
+        let entries: Vec<(Pubkey, u8)> = (20..26).map(|seed| (test_pubkey(seed), 1)).collect();
+        let winners = state::draw_lottery(&random_value, &entries, 4);
+        assert_eq!(winners.iter().filter(|&&won| won).count(), 4);
+        
+        // The draw is deterministic and doesn't depend on the order bids are passed in
+        assert_eq!(state::draw_lottery(&random_value, &entries, 4), winners);
+        let mut reversed = entries.clone();
+        reversed.reverse();
+        let mut reversed_winners = state::draw_lottery(&random_value, &reversed, 4);
+        reversed_winners.reverse();
+        assert_eq!(reversed_winners, winners);
+        
+        // Multi-ticket bids only win if they still fit in the remaining supply
+        let mixed = vec![(test_pubkey(30), 3), (test_pubkey(31), 3), (test_pubkey(32), 1)];
+        let mixed_winners = state::draw_lottery(&random_value, &mixed, 4);
+        let tickets_won: u32 = mixed.iter().zip(&mixed_winners).filter(|(_, &won)| won).map(|((_, quantity), _)| *quantity as u32).sum();
+        assert!(tickets_won <= 4);
+        assert!(!(mixed_winners[0] && mixed_winners[1]));
+        
+        // Switchboard account data parses into the same provider interface
+        let mut data = vec![0u8; state::SwitchboardRandomness::LEN];
+        data[..8].copy_from_slice(&state::SwitchboardRandomness::discriminator());
+        data[104..112].copy_from_slice(&100u64.to_le_bytes());
+        data[144..152].copy_from_slice(&102u64.to_le_bytes());
+        data[152..184].copy_from_slice(&[42u8; 32]);
+        let switchboard = state::SwitchboardRandomness::try_from_slice(&data).unwrap();
+        assert_eq!(switchboard.seed_slot(), 100);
+        assert_eq!(switchboard.reveal_value(102).unwrap(), random_value);
+        assert!(switchboard.reveal_value(103).is_err());
+        data[0] ^= 1;
+        assert!(state::SwitchboardRandomness::try_from_slice(&data).is_err());
+    }
+    
+    #[test]
+    fn test_award_batch_return_data() {
+        use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+        
+        // Skipped bids carry the same error code award_ticket would have failed with
+        let skipped = vec![
+            state::SkippedBid {
+                bid: test_pubkey(1),
+                error_code: escrow::error::ErrorCode::WalletTicketCapExceeded as u32 + anchor_lang::error::ERROR_CODE_OFFSET,
+            },
+            state::SkippedBid {
+                bid: test_pubkey(2),
+                error_code: escrow::error::ErrorCode::TicketAlreadyIssued as u32 + anchor_lang::error::ERROR_CODE_OFFSET,
+            },
+        ];
+        let return_data = skipped.try_to_vec().unwrap();
+        let decoded = Vec::<state::SkippedBid>::try_from_slice(&return_data).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].bid, test_pubkey(1));
+        assert_eq!(decoded[0].error_code, 0x177c);
+        assert_eq!(decoded[1].bid, test_pubkey(2));
+        
+        // Even if every bid in a full batch is skipped, the report fits in return data
+        let all_skipped: Vec<state::SkippedBid> = (0..MAX_AWARD_BATCH_BIDS as u8)
+            .map(|seed| state::SkippedBid { bid: test_pubkey(seed), error_code: 0x1770 })
+            .collect();
+        assert!(all_skipped.try_to_vec().unwrap().len() <= anchor_lang::solana_program::program::MAX_RETURN_DATA);
+    }
+    
+    #[test]
+    fn test_sequential_awards() {
+        // Simulate a first-come event with a small GA tier and a per-wallet cap
+        let mut event = state::Event {
+            organizer: test_pubkey(40),
+            ticket_supply: 5,
+            max_tickets_per_wallet: 4,
+            sequential_awards: true,
+            status: EVENT_STATUS_ACTIVE,
+            tiers: vec![state::TicketTier { supply: 5, ..Default::default() }],
+            ..Default::default()
+        };
+        
+        // Bids are numbered in the order they're placed
+        assert_eq!(event.next_bid_sequence().unwrap(), 0);
+        assert_eq!(event.next_bid_sequence().unwrap(), 1);
+        assert_eq!(event.next_bid_sequence().unwrap(), 2);
+        assert_eq!(event.bids_placed, 3);
+        
+        // Each bid in turn gets what's left for it, limited by the tier and the wallet cap
+        assert_eq!(event.sequential_award_quantity(0, 3, 0), 3);
+        assert_eq!(event.sequential_award_quantity(0, 3, 2), 2);
+        event.tiers[0].awarded = 3;
+        event.tickets_awarded = 3;
+        assert_eq!(event.sequential_award_quantity(0, 3, 0), 2);
+        
+        // Once nothing is left for a bid it can be skipped
+        assert_eq!(event.sequential_award_quantity(0, 1, 4), 0);
+        event.tiers[0].awarded = 5;
+        event.tickets_awarded = 5;
+        assert_eq!(event.sequential_award_quantity(0, 1, 0), 0);
+        assert_eq!(event.sequential_award_quantity(1, 1, 0), 0); // No such tier
+        
+        // Sealed bids are ranked by price, so they can't also be awarded in sequence
+        let start_time = test_time();
+        let sealed = state::Event {
+            ticket_supply: 10,
+            start_price: 2_000_000,
+            end_price: 1_000_000,
+            auction_start_time: start_time,
+            auction_end_time: start_time + 3600,
+            auction_type: AUCTION_TYPE_SEALED,
+            reveal_end_time: start_time + 7200,
+            sealed_deposit: 1_000_000,
+            tiers: vec![state::TicketTier { supply: 10, start_price: 2_000_000, end_price: 1_000_000, ..Default::default() }],
+            ..Default::default()
+        };
+        assert!(sealed.validate_params().is_ok());
+        let sealed_sequential = state::Event { sequential_awards: true, ..sealed };
+        assert!(sealed_sequential.validate_params().is_err());
+    }
+    
+    #[test]
+    fn test_waitlist() {
+        // Simulate a sold-out GA tier alongside a VIP tier with tickets left
+        let mut event = state::Event {
+            organizer: test_pubkey(41),
+            ticket_supply: 6,
+            tickets_awarded: 4,
+            status: EVENT_STATUS_ACTIVE,
+            tiers: vec![
+                state::TicketTier { supply: 4, awarded: 4, ..Default::default() },
+                state::TicketTier { supply: 2, ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        assert!(event.is_sold_out(0));
+        assert!(!event.is_sold_out(1));
+        assert!(!event.tiers[0].has_waitlist());
+        
+        // Bids after sellout take the next waitlist positions in order
+        event.tiers[0].waitlist_tail = 2;
+        assert!(event.tiers[0].has_waitlist());
+        let mut waitlisted = state::Bid {
+            bidder: test_pubkey(42),
+            event: test_pubkey(41),
+            amount: 2_000_000,
+            status: BID_STATUS_WAITLISTED,
+            quantity: 2,
+            waitlist_position: 0,
+            ..Default::default()
+        };
+        
+        // Releasing a ticket refunds the holder what they paid and settles a bid left with nothing
+        let mut holder = state::Bid {
+            bidder: test_pubkey(43),
+            event: test_pubkey(41),
+            amount: 3_000_000,
+            status: BID_STATUS_AWARDED,
+            quantity: 1,
+            quantity_awarded: 1,
+            ..Default::default()
+        };
+        assert_eq!(holder.release_tickets(1).unwrap(), 3_000_000);
+        assert_eq!(holder.status, BID_STATUS_REFUNDED);
+        assert!(holder.settled);
+        assert!(holder.release_tickets(1).is_err());
+        
+        // The head of the waitlist takes over the ticket and moves the waitlist on
+        waitlisted.status = BID_STATUS_AWARDED;
+        waitlisted.quantity_awarded = 1;
+        event.tiers[0].waitlist_head += 1;
+        assert!(event.tiers[0].has_waitlist());
+        
+        // The promoted bid gets back what it bid for beyond the released ticket once finalized
+        event.status = EVENT_STATUS_FINALIZED;
+        event.auction_close_price = 2_000_000;
+        event.tiers[0].close_price = 2_000_000;
+        assert_eq!(waitlisted.settle(&event).unwrap(), 2_000_000);
+        
+        // Bids never promoted stay locked in while bidding is open, then get a full refund
+        let mut never_promoted = state::Bid {
+            bidder: test_pubkey(44),
+            event: test_pubkey(41),
+            amount: 2_000_000,
+            status: BID_STATUS_WAITLISTED,
+            quantity: 3,
+            waitlist_position: 1,
+            ..Default::default()
+        };
+        event.status = EVENT_STATUS_ACTIVE;
+        assert!(never_promoted.settle(&event).is_err());
+        event.status = EVENT_STATUS_FINALIZED;
+        assert_eq!(never_promoted.settle(&event).unwrap(), 6_000_000);
+        assert_eq!(never_promoted.status, BID_STATUS_REFUNDED);
+        
+        // Wallet holdings drop when a ticket is released
+        let event_key = test_pubkey(41);
+        let mut user = state::User::default();
+        user.record_tickets(&event_key, 2).unwrap();
+        user.release_tickets(&event_key, 1);
+        assert_eq!(user.tickets_held(&event_key), 1);
+    }
+    
+    #[test]
+    fn test_instant_buy() {
+        // Simulate an instant-buy event part way through selling its tickets
+        let start_time = test_time();
+        let mut event = state::Event {
+            organizer: test_pubkey(45),
+            ticket_supply: 5,
+            tickets_awarded: 3,
+            start_price: 2_000_000,
+            end_price: 1_000_000,
+            auction_start_time: start_time,
+            auction_end_time: start_time + 3600,
+            instant_buy: true,
+            status: EVENT_STATUS_CREATED,
+            tiers: vec![state::TicketTier {
+                supply: 5,
+                awarded: 3,
+                start_price: 2_000_000,
+                end_price: 1_000_000,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(event.validate_params().is_ok());
+        
+        // Purchases only go through while there are enough tickets left for them
+        assert_eq!(event.tickets_left(0), 2);
+        assert_eq!(event.tickets_left(1), 0); // No such tier
+        
+        // The event's total supply limits every tier
+        event.ticket_supply = 4;
+        assert_eq!(event.tickets_left(0), 1);
+        event.ticket_supply = 5;
+        
+        // Instant buys are already first-come, so they can't be combined with sequential awards
+        event.sequential_awards = true;
+        assert!(event.validate_params().is_err());
+        event.sequential_awards = false;
+        
+        // Sealed bids can't be bought outright
+        event.auction_type = AUCTION_TYPE_SEALED;
+        event.reveal_end_time = start_time + 7200;
+        event.sealed_deposit = 1_000_000;
+        assert!(event.validate_params().is_err());
+        event.instant_buy = false;
+        assert!(event.validate_params().is_ok());
+    }
+    
+    #[test]
+    fn test_bid_max_price() {
+        // Simulate a tier whose price falls from 2M to 1M over an hour
+        let start_time = test_time();
+        let event = state::Event {
+            start_price: 2_000_000,
+            end_price: 1_000_000,
+            auction_start_time: start_time,
+            auction_end_time: start_time + 3600,
+            status: EVENT_STATUS_ACTIVE,
+            tiers: vec![state::TicketTier { supply: 10, start_price: 2_000_000, end_price: 1_000_000, ..Default::default() }],
+            ..Default::default()
+        };
+        let halfway_time = start_time + 1800;
+        
+        // Bids are placed at the current price whatever maximum covers it
+        assert_eq!(event.bid_price(0, 1_500_000, halfway_time).unwrap(), 1_500_000);
+        assert_eq!(event.bid_price(0, 2_000_000, halfway_time).unwrap(), 1_500_000);
+        
+        // A maximum set a few seconds ago still clears once the price has moved down
+        assert_eq!(event.bid_price(0, 1_500_000, halfway_time + 10).unwrap(), event.get_current_auction_price(halfway_time + 10));
+        
+        // Only a maximum below the current price is rejected
+        assert!(event.bid_price(0, 1_499_999, halfway_time).is_err());
+        assert!(event.bid_price(1, 2_000_000, halfway_time).is_err()); // No such tier
+    }
+    
+    #[test]
+    fn test_limit_bids() {
+        // Simulate a tier whose price falls from 2M to 1M over an hour
+        let start_time = test_time();
+        let mut event = state::Event {
+            ticket_supply: 10,
+            start_price: 2_000_000,
+            end_price: 1_000_000,
+            auction_start_time: start_time,
+            auction_end_time: start_time + 3600,
+            status: EVENT_STATUS_ACTIVE,
+            tiers: vec![state::TicketTier { supply: 10, start_price: 2_000_000, end_price: 1_000_000, ..Default::default() }],
+            ..Default::default()
+        };
+        
+        // Limits have to be below the current price and reachable before the auction ends
+        assert!(event.validate_limit_price(0, 1_200_000, start_time).is_ok());
+        assert!(event.validate_limit_price(0, 2_000_000, start_time).is_err()); // Just place the bid
+        assert!(event.validate_limit_price(0, 999_999, start_time).is_err()); // Below the end price
+        assert!(event.validate_limit_price(1, 1_200_000, start_time).is_err()); // No such tier
+        
+        // The bid waits while the price is above its limit
+        let mut bid = state::Bid {
+            bidder: test_pubkey(46),
+            event: test_pubkey(47),
+            amount: 1_200_000,
+            status: BID_STATUS_LIMIT,
+            quantity: 2,
+            ..Default::default()
+        };
+        assert!(event.bid_price(bid.tier, bid.amount, start_time + 1800).is_err());
+        assert!(!bid.can_award());
+        
+        // Once the price falls to the limit, it becomes an ordinary bid with the next sequence number
+        let execute_time = start_time + 2880;
+        assert_eq!(event.bid_price(bid.tier, bid.amount, execute_time).unwrap(), 1_200_000);
+        assert_eq!(bid.execute_limit(1_200_000).unwrap(), 0);
+        event.bids_placed = 3;
+        bid.open(&mut event, 500).unwrap();
+        assert_eq!(bid.status, BID_STATUS_PENDING);
+        assert_eq!(bid.sequence, 3);
+        assert_eq!(bid.slot, 500);
+        assert!(bid.can_award());
+        
+        // A bid executed after the price has fallen past its limit is placed at the current price,
+        // and what was escrowed above it is returned
+        let late_time = start_time + 3000;
+        let mut late = state::Bid { amount: 1_200_000, status: BID_STATUS_LIMIT, quantity: 2, ..Default::default() };
+        let current_price = event.bid_price(late.tier, late.amount, late_time).unwrap();
+        assert!(current_price < 1_200_000);
+        assert_eq!(late.execute_limit(current_price).unwrap(), 2 * (1_200_000 - current_price));
+        assert_eq!(late.amount, current_price);
+        late.open(&mut event, 501).unwrap();
+        assert!(late.execute_limit(current_price).is_err());
+        
+        // Limit bids that never execute are refunded in full once the auction is over
+        let mut unexecuted = state::Bid { amount: 1_100_000, status: BID_STATUS_LIMIT, quantity: 2, ..Default::default() };
+        event.status = EVENT_STATUS_FINALIZED;
+        assert_eq!(unexecuted.settle(&event).unwrap(), 2_200_000);
+        assert_eq!(unexecuted.status, BID_STATUS_REFUNDED);
+    }
+    
+    #[test]
+    fn test_bid_withdrawal_policy() {
+        // Simulate an event where withdrawals cost 5% from an hour after the auction starts
+        let start_time = test_time();
+        let cutoff = start_time + 3600;
+        let mut event = state::Event {
+            organizer: test_pubkey(48),
+            auction_start_time: start_time,
+            auction_end_time: start_time + 7200,
+            withdrawal_cutoff: cutoff,
+            withdrawal_fee_bps: 500,
+            status: EVENT_STATUS_ACTIVE,
+            ..Default::default()
+        };
+        let pending_bid = state::Bid {
+            bidder: test_pubkey(49),
+            amount: 2_000_000,
+            status: BID_STATUS_PENDING,
+            quantity: 2,
+            ..Default::default()
+        };
+        
+        // Free before the cutoff, the fee goes to the organizer after it
+        let mut early = pending_bid.clone();
+        assert_eq!(early.withdraw(&mut event, cutoff - 1).unwrap(), (4_000_000, 0));
+        assert!(early.settled);
+        assert_eq!(early.status, BID_STATUS_REFUNDED);
+        assert!(early.withdraw(&mut event, cutoff - 1).is_err()); // Only once
+        let mut late = pending_bid.clone();
+        assert_eq!(late.withdraw(&mut event, cutoff).unwrap(), (3_800_000, 200_000));
+        
+        // No cutoff means withdrawals are always free
+        let free_event = state::Event { withdrawal_cutoff: 0, ..event.clone() };
+        assert_eq!(free_event.withdrawal_fee(4_000_000, cutoff + 1000).unwrap(), 0);
+        
+        // Pending bids can't skip the policy by being refunded mid-auction
+        let mut refunded = pending_bid.clone();
+        assert!(refunded.settle(&event).is_err());
+        
+        // Once award processing starts, bids stay in until the auction is over
+        event.award_processing_started = true;
+        let mut frozen = pending_bid.clone();
+        assert!(frozen.withdraw(&mut event, cutoff - 1).is_err());
+        assert_eq!(frozen.status, BID_STATUS_PENDING);
+        event.status = EVENT_STATUS_FINALIZED;
+        assert!(frozen.withdraw(&mut event, cutoff - 1).is_err());
+        assert_eq!(frozen.settle(&event).unwrap(), 4_000_000);
+        
+        // Withdrawn bids in a sequential queue stay until the queue has moved past them
+        let queue_event = state::Event {
+            sequential_awards: true,
+            next_award_sequence: 2,
+            status: EVENT_STATUS_ACTIVE,
+            ..Default::default()
+        };
+        let queued = state::Bid { sequence: 2, slot: 100, ..Default::default() };
+        let passed = state::Bid { sequence: 1, slot: 100, ..Default::default() };
+        let never_queued = state::Bid { status: BID_STATUS_LIMIT, ..Default::default() };
+        assert!(queued.holds_queue_position(&queue_event));
+        assert!(!passed.holds_queue_position(&queue_event));
+        assert!(!never_queued.holds_queue_position(&queue_event));
+        
+        // Sealed bids can't be withdrawn while they're committed, once revealed, or while being ranked
+        let mut sealed_event = state::Event {
+            auction_type: AUCTION_TYPE_SEALED,
+            status: EVENT_STATUS_ACTIVE,
+            ..Default::default()
+        };
+        let mut committed = state::Bid { status: BID_STATUS_COMMITTED, ..pending_bid.clone() };
+        assert!(committed.withdraw(&mut sealed_event, cutoff - 1).is_err());
+        let mut revealed = pending_bid.clone();
+        assert!(revealed.withdraw(&mut sealed_event, cutoff - 1).is_err());
+        sealed_event.sealed_bids_ranked = true;
+        sealed_event.award_processing_started = true;
+        assert!(revealed.withdraw(&mut sealed_event, cutoff - 1).is_err());
+        assert_eq!(revealed.status, BID_STATUS_PENDING);
+    }
+    
+    #[test]
+    fn test_ticket_transfer_policy() {
+        // Simulate an event that closed at 1 SOL (and its VIP tier at 3 SOL), with resale capped at
+        // 120% and a 10% royalty
+        let event = state::Event {
+            organizer: test_pubkey(50),
+            auction_close_price: 1_000_000_000,
+            status: EVENT_STATUS_FINALIZED,
+            transfer_policy: TRANSFER_POLICY_CAPPED,
+            resale_cap_bps: 12_000,
+            royalty_bps: 1_000,
+            tiers: vec![
+                state::TicketTier { close_price: 1_000_000_000, ..Default::default() },
+                state::TicketTier { close_price: 3_000_000_000, ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        
+        // The cap scales with the admissions on the ticket, and the royalty comes out of the price
+        assert_eq!(event.resale_royalty(2_400_000_000, 0, 2).unwrap(), 240_000_000);
+        assert!(event.resale_royalty(2_400_000_001, 0, 2).is_err());
+        assert!(event.resale_royalty(1_200_000_001, 0, 1).is_err());
+        assert_eq!(event.resale_royalty(0, 0, 1).unwrap(), 0); // Gifts are always allowed
+        
+        // Each tier is capped against its own close price
+        assert_eq!(event.resale_royalty(3_600_000_000, 1, 1).unwrap(), 360_000_000);
+        assert!(event.resale_royalty(3_600_000_001, 1, 1).is_err());
+        assert!(event.resale_royalty(0, 2, 1).is_err()); // No such tier
+        
+        // Free transfers have no cap, and non-transferable tickets can't even be gifted
+        let free_event = state::Event { transfer_policy: TRANSFER_POLICY_FREE, ..event.clone() };
+        assert_eq!(free_event.resale_royalty(10_000_000_000, 0, 1).unwrap(), 1_000_000_000);
+        let locked_event = state::Event { transfer_policy: TRANSFER_POLICY_NONE, ..event.clone() };
+        assert!(locked_event.resale_royalty(0, 0, 1).is_err());
+        
+        // Until the auction closes there is no price to cap against, so capped tickets can only be gifted
+        let mut open_event = state::Event { auction_close_price: 0, ..event.clone() };
+        open_event.tiers[0].close_price = 0;
+        assert!(open_event.resale_royalty(1, 0, 1).is_err());
+        assert_eq!(open_event.resale_royalty(0, 0, 1).unwrap(), 0);
+        
+        // Tickets only change hands while the event is running or once it's finalized, not once they've been refunded
+        assert!(event.can_transfer_tickets());
+        assert!(state::Event { status: EVENT_STATUS_ACTIVE, ..event.clone() }.can_transfer_tickets());
+        assert!(!state::Event { status: EVENT_STATUS_FAILED, ..event.clone() }.can_transfer_tickets());
+        assert!(!state::Event { status: EVENT_STATUS_CANCELLED, ..event.clone() }.can_transfer_tickets());
+        
+        // Capped transfers need a cap within range, other policies none, and the policy has to be a known one
+        assert!(event.validate_transfer_policy().is_ok());
+        assert!(state::Event { resale_cap_bps: 0, ..event.clone() }.validate_transfer_policy().is_err());
+        assert!(state::Event { resale_cap_bps: MAX_RESALE_CAP_BPS + 1, ..event.clone() }.validate_transfer_policy().is_err());
+        assert!(free_event.validate_transfer_policy().is_err());
+        assert!(state::Event { resale_cap_bps: 0, ..free_event.clone() }.validate_transfer_policy().is_ok());
+        assert!(state::Event { transfer_policy: TRANSFER_POLICY_CAPPED + 1, resale_cap_bps: 0, ..event.clone() }.validate_transfer_policy().is_err());
+        assert!(state::Event { royalty_bps: TOTAL_BASIS_POINTS + 1, ..event.clone() }.validate_transfer_policy().is_err());
+    }
+    
+    #[test]
+    fn test_close_price_above_awarded_bid() {
+        // Simulate a finished auction where the cheapest winner paid 0.6 SOL per ticket
+        let mut event = state::Event {
+            organizer: test_pubkey(51),
+            ticket_supply: 10,
+            start_price: 1_000_000_000,
+            end_price: 100_000_000,
+            auction_start_time: test_time() - 7200,
+            auction_end_time: test_time() - 3600,
+            status: EVENT_STATUS_ACTIVE,
+            tiers: vec![state::TicketTier {
+                supply: 10,
+                start_price: 1_000_000_000,
+                end_price: 100_000_000,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        for (amount, quantity) in [(900_000_000, 2), (600_000_000, 1)] {
+            event.tickets_awarded += quantity;
+            event.tiers[0].awarded += quantity;
+            event.tiers[0].record_award_price(amount);
+        }
+        assert_eq!(event.tiers[0].lowest_awarded_bid, 600_000_000);
+        
+        // Closing above what a winner paid would take other bidders' escrow to cover the difference
+        let mut overpriced = event.clone();
+        assert!(overpriced.finalize(700_000_000, vec![], test_time()).is_err());
+        assert_eq!(overpriced.status, EVENT_STATUS_ACTIVE);
+        
+        // Closing at the lowest winning bid leaves every winner with a refund they can be paid
+        event.finalize(600_000_000, vec![], test_time()).unwrap();
+        assert_eq!(event.proceeds(), Some(1_800_000_000));
+        let mut cheapest_bid = state::Bid {
+            amount: 600_000_000,
+            status: BID_STATUS_AWARDED,
+            quantity: 1,
+            quantity_awarded: 1,
+            ..Default::default()
+        };
+        assert_eq!(cheapest_bid.settle(&event).unwrap(), 0);
+    }
+    
+    #[test]
+    fn test_tier_close_price_above_awarded_bid() {
+        // Simulate a finished two-tier auction where the cheapest VIP winner paid 3 SOL per ticket
+        let mut event = state::Event {
+            organizer: test_pubkey(52),
+            ticket_supply: 15,
+            start_price: 1_000_000_000,
+            end_price: 100_000_000,
+            auction_start_time: test_time() - 7200,
+            auction_end_time: test_time() - 3600,
+            status: EVENT_STATUS_ACTIVE,
+            tiers: vec![
+                state::TicketTier { supply: 10, start_price: 1_000_000_000, end_price: 100_000_000, ..Default::default() },
+                state::TicketTier { supply: 5, start_price: 5_000_000_000, end_price: 1_000_000_000, ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        event.tickets_awarded = 3;
+        event.tiers[0].awarded = 1;
+        event.tiers[0].record_award_price(500_000_000);
+        event.tiers[1].awarded = 2;
+        event.tiers[1].record_award_price(4_000_000_000);
+        event.tiers[1].record_award_price(3_000_000_000);
+        
+        // The VIP tier can't close above its cheapest winner, even though it's within the tier's range
+        let mut overpriced = event.clone();
+        assert!(overpriced.finalize(500_000_000, vec![3_500_000_000], test_time()).is_err());
+        assert_eq!(overpriced.status, EVENT_STATUS_ACTIVE);
+        assert_eq!(overpriced.tiers[1].close_price, 0); // Nothing is set when any tier is rejected
+        
+        // Each tier at or below its own lowest winning bid is fine
+        event.finalize(500_000_000, vec![3_000_000_000], test_time()).unwrap();
+        assert_eq!(event.tier_close_price(1), Some(3_000_000_000));
+        assert_eq!(event.proceeds(), Some(6_500_000_000));
+    }
+    
+    #[test]
+    fn test_proceeds_limited_by_awarded_bids() {
+        // Simulate an event finalized with a close price above what one of its winners paid
+        let mut event = state::Event {
+            organizer: test_pubkey(53),
+            ticket_supply: 10,
+            status: EVENT_STATUS_FINALIZED,
+            auction_close_price: 700_000_000,
+            tickets_awarded: 3,
+            tiers: vec![state::TicketTier {
+                supply: 10,
+                awarded: 3,
+                close_price: 700_000_000,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        event.tiers[0].record_award_price(900_000_000);
+        event.tiers[0].record_award_price(600_000_000);
+        
+        // Each ticket is paid out at no more than the lowest awarded bid, not the close price
+        assert_eq!(event.proceeds(), Some(3 * 600_000_000));
+        
+        // At or below every awarded bid, proceeds are paid at the close price
+        event.tiers[0].close_price = 500_000_000;
+        assert_eq!(event.proceeds(), Some(3 * 500_000_000));
+    }
+    
+    #[test]
+    fn test_sealed_bid_ranking() {
+        // Revealed bids as (amount per ticket, sequence, quantity) competing for five tickets
+        let entries = [
+            (400_000_000, 0, 2),
+            (900_000_000, 1, 1),
+            (600_000_000, 2, 2),
+            (600_000_000, 3, 1),
+            (300_000_000, 4, 1),
+        ];
+        
+        // Highest bids win first, with ties going to the earlier bid
+        assert_eq!(state::sealed_bid_winners(&entries, 4), vec![false, true, true, true, false]);
+        
+        // Once a bid doesn't fit, no lower bid is taken past it, even one that would fit
+        assert_eq!(state::sealed_bid_winners(&entries, 5), vec![false, true, true, true, false]);
+        assert_eq!(state::sealed_bid_winners(&entries, 6), vec![true, true, true, true, false]);
+        
+        // The outcome doesn't depend on the order bids are passed in
+        let mut reversed = entries;
+        reversed.reverse();
+        let mut reversed_winners = state::sealed_bid_winners(&reversed, 4);
+        reversed_winners.reverse();
+        assert_eq!(reversed_winners, state::sealed_bid_winners(&entries, 4));
+        
+        // Winners never take more than the supply
+        assert_eq!(state::sealed_bid_winners(&entries, 0), vec![false; 5]);
+    }
+    
+    #[test]
+    fn test_lottery_holds_back_tickets_for_higher_bids() {
+        // Simulate an auction with four base tier tickets that closed at its end price
+        let mut event = state::Event {
+            organizer: test_pubkey(54),
+            ticket_supply: 4,
+            start_price: 1_000_000_000,
+            end_price: 100_000_000,
+            status: EVENT_STATUS_ACTIVE,
+            tiers: vec![state::TicketTier { supply: 4, start_price: 1_000_000_000, end_price: 100_000_000, ..Default::default() }],
+            ..Default::default()
+        };
+        
+        // A pool bid at the end price and a higher two-ticket bid that outbids the pool
+        let mut pool_bid = state::Bid { amount: 100_000_000, quantity: 1, ..Default::default() };
+        pool_bid.open(&mut event, 10).unwrap();
+        let mut higher_bid = state::Bid { amount: 400_000_000, quantity: 2, ..Default::default() };
+        higher_bid.open(&mut event, 11).unwrap();
+        assert_eq!(event.lottery_pool_bids, 1);
+        assert!(event.is_above_lottery_pool(higher_bid.tier, higher_bid.amount));
+        assert_eq!(event.tickets_bid_above_pool, 2);
+        
+        // The draw only gets the tickets the higher bid doesn't need
+        assert_eq!(event.lottery_tickets_available(), 2);
+        event.tiers[0].awarded = 1;
+        assert_eq!(event.lottery_tickets_available(), 1);
+        event.tiers[0].awarded = 3;
+        assert_eq!(event.lottery_tickets_available(), 0);
     }
-}
\ No newline at end of file
+}
@@ -3,6 +3,9 @@ import * as programClient from "../dist/js-client";
 import { type KeyPairSigner, type Address } from "@solana/kit";
 import { PublicKey } from "@solana/web3.js";
 
+const TICKETS_PER_INVENTORY_PAGE = 256; // Matches the program's inventory page size
+const MAX_ASSETS_PER_REGISTRATION = 24; // Matches the program's per-transaction chunk limit
+
 // Helper to calculate the current Dutch auction price
 export function calculateCurrentPrice(
   event: {
@@ -10,17 +13,20 @@ export function calculateCurrentPrice(
     endPrice: bigint;
     auctionStartTime: bigint;
     auctionEndTime: bigint;
+    extendedEndTime?: bigint;
   },
   now: number = Math.floor(Date.now() / 1000)
 ): bigint {
+  // Anti-sniping extensions push the end of the price curve back
+  const effectiveEndTime = Math.max(Number(event.auctionEndTime), Number(event.extendedEndTime ?? 0n));
   if (now <= Number(event.auctionStartTime)) {
     return event.startPrice;
-  } else if (now >= Number(event.auctionEndTime)) {
+  } else if (now >= effectiveEndTime) {
     return event.endPrice;
   } else {
     // Match Rust integer arithmetic exactly to avoid precision errors
     const elapsed = BigInt(now - Number(event.auctionStartTime));
-    const duration = BigInt(Number(event.auctionEndTime) - Number(event.auctionStartTime));
+    const duration = BigInt(effectiveEndTime - Number(event.auctionStartTime));
     const priceDiff = event.startPrice - event.endPrice;
     
     // Rust calculation: start_price - ((price_diff * elapsed) / duration)
@@ -44,6 +50,9 @@ export async function createEvent(
     endPrice: bigint;
     startTime: number;
     endTime: number;
+    minTicketsSold?: number;
+    maxTicketsPerWallet?: number;
+    autoActivate?: boolean;
   }
 ) {
   // Create a unique organizer if not provided to avoid PDA collisions
@@ -80,6 +89,9 @@ export async function createEvent(
     endPrice: params.endPrice,
     auctionStartTime: BigInt(params.startTime),
     auctionEndTime: BigInt(params.endTime),
+    minTicketsSold: params.minTicketsSold ?? 0,
+    maxTicketsPerWallet: params.maxTicketsPerWallet ?? 0,
+    autoActivate: params.autoActivate ?? false,
   });
 
   // Get the event address from the instruction
@@ -135,6 +147,9 @@ export async function createAndActivateEvent(
     endPrice: bigint;
     auctionStartTime: bigint;
     auctionEndTime: bigint;
+    minTicketsSold?: number;
+    maxTicketsPerWallet?: number;
+    autoActivate?: boolean;
   }
 ) {
   // Create the event
@@ -151,6 +166,9 @@ export async function createAndActivateEvent(
     endPrice: params.endPrice,
     auctionStartTime: params.auctionStartTime,
     auctionEndTime: params.auctionEndTime,
+    minTicketsSold: params.minTicketsSold ?? 0,
+    maxTicketsPerWallet: params.maxTicketsPerWallet ?? 0,
+    autoActivate: params.autoActivate ?? false,
   });
 
   // Get the event address from the instruction
@@ -192,6 +210,73 @@ export async function createAndActivateEvent(
   };
 }
 
+/**
+ * Derives the PDA of an event's ticket inventory page
+ */
+export function getInventoryPageAddress(event: Address, pageIndex: number): Address {
+  const programIdPubkey = new PublicKey(programClient.ESCROW_PROGRAM_ADDRESS);
+  const pageIndexBuffer = Buffer.alloc(4);
+  pageIndexBuffer.writeUInt32LE(pageIndex);
+  const [inventoryPage] = PublicKey.findProgramAddressSync(
+    [Buffer.from("inventory"), new PublicKey(event).toBuffer(), pageIndexBuffer],
+    programIdPubkey
+  );
+  return inventoryPage.toString() as Address;
+}
+
+/**
+ * Registers minted cNFT asset IDs in an event's inventory, creating pages as needed
+ * and sending the IDs in chunks that fit in a transaction
+ */
+export async function registerTicketAssets(
+  connection: Connection,
+  params: {
+    organizer: KeyPairSigner;
+    event: Address;
+    assetIds: Array<PublicKey>;
+    startIndex?: number;
+  }
+) {
+  const startIndex = params.startIndex ?? 0;
+  const txs: Array<string> = [];
+
+  for (let offset = 0; offset < params.assetIds.length;) {
+    const ticketIndex = startIndex + offset;
+    const pageIndex = Math.floor(ticketIndex / TICKETS_PER_INVENTORY_PAGE);
+    const inventoryPage = getInventoryPageAddress(params.event, pageIndex);
+
+    // Create each page the first time we write into it
+    const instructions = [];
+    if (ticketIndex % TICKETS_PER_INVENTORY_PAGE === 0) {
+      instructions.push(await programClient.getCreateTicketInventoryPageInstructionAsync({
+        organizer: params.organizer,
+        event: params.event,
+        inventoryPage,
+        pageIndex,
+      }));
+    }
+
+    // Chunks can't cross a page boundary
+    const pageRemaining = TICKETS_PER_INVENTORY_PAGE - (ticketIndex % TICKETS_PER_INVENTORY_PAGE);
+    const chunkSize = Math.min(MAX_ASSETS_PER_REGISTRATION, pageRemaining, params.assetIds.length - offset);
+    instructions.push(await programClient.getRegisterTicketAssetsInstructionAsync({
+      organizer: params.organizer,
+      event: params.event,
+      inventoryPage,
+      startIndex: ticketIndex,
+      assetIds: params.assetIds.slice(offset, offset + chunkSize).map(assetId => assetId.toString() as Address),
+    }));
+
+    txs.push(await connection.sendTransactionFromInstructions({
+      feePayer: params.organizer,
+      instructions,
+    }));
+    offset += chunkSize;
+  }
+
+  return { txs };
+}
+
 /**
  * Places a bid on a TicketFair event at the current auction price
  */
@@ -201,6 +286,8 @@ export async function placeBid(
     bidder: KeyPairSigner;
     event: Address;
     amount: bigint;
+    quantity?: number;
+    tier?: number;
   }
 ) {
   // Calculate the event PDA authority
@@ -241,13 +328,32 @@ export async function placeBid(
     ? params.amount 
     : BigInt(params.amount.toString());
 
+  // Instant-buy events issue the ticket in the same instruction, from the next inventory pages
+  let instantBuyAccounts = {};
+  if (eventData.data.instantBuy) {
+    const [ticketAddress] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ticket"), eventPubkey.toBuffer(), bidderPubkey.toBuffer()],
+      programIdPubkey
+    );
+    const firstPage = Math.floor(eventData.data.assetsAwarded / TICKETS_PER_INVENTORY_PAGE);
+    const lastPage = Math.floor((eventData.data.assetsAwarded + (params.quantity ?? 1) - 1) / TICKETS_PER_INVENTORY_PAGE);
+    instantBuyAccounts = {
+      ticket: ticketAddress.toString(),
+      inventoryPage: getInventoryPageAddress(params.event, firstPage),
+      nextInventoryPage: lastPage > firstPage ? getInventoryPageAddress(params.event, lastPage) : undefined,
+    };
+  }
+
   // Create the instruction for placing a bid
   // Make sure to use a string for eventPda and a properly converted bigint for amount
   const placeBidIx = await programClient.getPlaceBidInstructionAsync({
     bidder: params.bidder,
     event: params.event,
     eventPda: eventPdaAddress.toString(),
-    amount: bidAmount, // Use the validated bidAmount as 'amount'
+    maxPrice: bidAmount, // The bid is placed at the current price, as long as it is no more than this
+    quantity: params.quantity ?? 1,
+    tier: params.tier ?? 0,
+    ...instantBuyAccounts,
   });
 
   // Send the transaction
@@ -274,7 +380,7 @@ export async function awardTicket(
     logWrapper: Address;
     compressionProgram: Address;
     noopProgram: Address;
-    cnftAssetId: PublicKey;
+    quantity?: number;
   }
 ) {
   // Calculate the ticket account PDA
@@ -287,6 +393,11 @@ export async function awardTicket(
     programIdPubkey
   );
 
+  // The awarded assets come from the inventory pages holding the next unawarded tickets
+  const eventData = await programClient.fetchEvent(connection.rpc, params.event);
+  const firstPage = Math.floor(eventData.data.assetsAwarded / TICKETS_PER_INVENTORY_PAGE);
+  const lastPage = Math.floor((eventData.data.assetsAwarded + (params.quantity ?? 1) - 1) / TICKETS_PER_INVENTORY_PAGE);
+
   // Create the award ticket instruction
   const awardTicketIx = await programClient.getAwardTicketInstructionAsync({
     organizer: params.organizer,
@@ -298,7 +409,9 @@ export async function awardTicket(
     logWrapper: params.logWrapper,
     compressionProgram: params.compressionProgram,
     noopProgram: params.noopProgram,
-    cnftAssetId: params.cnftAssetId,
+    inventoryPage: getInventoryPageAddress(params.event, firstPage),
+    nextInventoryPage: lastPage > firstPage ? getInventoryPageAddress(params.event, lastPage) : undefined,
+    quantity: params.quantity ?? 1,
   });
 
   // Send the transaction
@@ -348,6 +461,7 @@ export async function finalizeAuction(
     organizer: KeyPairSigner;
     event: Address;
     closePrice: bigint;
+    tierClosePrices?: bigint[];
   }
 ) {
   // Create the finalize auction instruction
@@ -355,6 +469,7 @@ export async function finalizeAuction(
     organizer: params.organizer,
     event: params.event,
     closePrice: params.closePrice,
+    tierClosePrices: params.tierClosePrices ?? [],
   });
 
   // Send the transaction
@@ -4,9 +4,11 @@ import * as programClient from "../dist/js-client";
 import { type KeyPairSigner, type Address, lamports } from "@solana/kit";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
 import { ONE_SOL } from "./escrow.test-helpers";
+import { getInventoryPageAddress, registerTicketAssets } from "../src/ticketfair-api";
 
 // Constants
 const ONE_BILLION = 1_000_000_000; // 1 SOL in lamports (10^9)
+const TICKETS_PER_INVENTORY_PAGE = 256; // Matches the program's inventory page size
 
 // Helper to calculate the current Dutch auction price
 export function calculateCurrentPrice(
@@ -15,6 +17,7 @@ export function calculateCurrentPrice(
     endPrice: bigint | number | string;
     auctionStartTime: bigint | number | string;
     auctionEndTime: bigint | number | string;
+    extendedEndTime?: bigint | number | string;
   },
   now: number = Math.floor(Date.now() / 1000)
 ): bigint {
@@ -68,7 +71,11 @@ export function calculateCurrentPrice(
     const startPrice = safeToBigInt(event.startPrice);
     const endPrice = safeToBigInt(event.endPrice);
     const auctionStartTime = Number(safeToBigInt(event.auctionStartTime));
-    const auctionEndTime = Number(safeToBigInt(event.auctionEndTime));
+    // Anti-sniping extensions push the end of the price curve back
+    const auctionEndTime = Math.max(
+      Number(safeToBigInt(event.auctionEndTime)),
+      event.extendedEndTime === undefined ? 0 : Number(safeToBigInt(event.extendedEndTime))
+    );
     
     console.log("Converted values:", {
       startPrice: startPrice.toString(),
@@ -127,6 +134,9 @@ export async function createAndActivateEvent(
     endPrice: bigint;
     auctionStartTime: bigint;
     auctionEndTime: bigint;
+    minTicketsSold?: number;
+    maxTicketsPerWallet?: number;
+    autoActivate?: boolean;
   }
 ) {
   // Create a unique organizer for each event to avoid PDA collisions
@@ -156,6 +166,9 @@ export async function createAndActivateEvent(
     endPrice: params.endPrice,
     auctionStartTime: params.auctionStartTime,
     auctionEndTime: params.auctionEndTime,
+    minTicketsSold: params.minTicketsSold ?? 0,
+    maxTicketsPerWallet: params.maxTicketsPerWallet ?? 0,
+    autoActivate: params.autoActivate ?? false,
   });
 
   // Get the event address from the instruction
@@ -196,6 +209,13 @@ export async function createAndActivateEvent(
   // Wait for transaction confirmation
   await new Promise(resolve => setTimeout(resolve, 600));
 
+  // Stock the inventory with placeholder asset IDs so tickets can be awarded
+  await registerTicketAssets(connection, {
+    organizer: uniqueOrganizer,
+    event: eventAddress,
+    assetIds: Array.from({ length: params.ticketSupply }, () => PublicKey.unique()),
+  });
+
   // Return the event address, PDA, and the unique organizer for later use
   return { 
     eventAddress, 
@@ -211,6 +231,8 @@ export async function placeBid(
     bidder: KeyPairSigner;
     event: Address;
     amount: bigint;
+    quantity?: number;
+    tier?: number;
     eventPdaOverride?: string; // Optional override for the event PDA
   }
 ) {
@@ -342,12 +364,32 @@ export async function placeBid(
     
     // Try a simpler approach with a direct amount value
     try {
+      // Instant-buy events issue the ticket in the same instruction, from the next inventory pages
+      const eventData = await programClient.fetchEvent(connection.rpc, params.event);
+      let instantBuyAccounts = {};
+      if (eventData.data.instantBuy) {
+        const [ticketAddress] = PublicKey.findProgramAddressSync(
+          [Buffer.from("ticket"), eventPubkey.toBuffer(), bidderPubkey.toBuffer()],
+          programIdPubkey
+        );
+        const firstPage = Math.floor(eventData.data.assetsAwarded / TICKETS_PER_INVENTORY_PAGE);
+        const lastPage = Math.floor((eventData.data.assetsAwarded + (params.quantity ?? 1) - 1) / TICKETS_PER_INVENTORY_PAGE);
+        instantBuyAccounts = {
+          ticket: ticketAddress.toString(),
+          inventoryPage: getInventoryPageAddress(params.event, firstPage),
+          nextInventoryPage: lastPage > firstPage ? getInventoryPageAddress(params.event, lastPage) : undefined,
+        };
+      }
+
       // Create a simple object with required properties and explicit typing
       const bidInstruction = {
         bidder: params.bidder,
         event: params.event,
         eventPda: eventPdaAddress.toString(), // Convert to string
-        amount: Number(bidAmount) // Use number type for compatibility
+        maxPrice: Number(bidAmount), // Use number type for compatibility
+        quantity: params.quantity ?? 1,
+        tier: params.tier ?? 0,
+        ...instantBuyAccounts,
       };
       
       console.log("Instruction data:", JSON.stringify(bidInstruction, (key, value) => 
@@ -393,7 +435,7 @@ export async function awardTicket(
     logWrapper: Address;
     compressionProgram: Address;
     noopProgram: Address;
-    cnftAssetId: PublicKey;
+    quantity?: number;
   }
 ) {
   try {
@@ -443,6 +485,11 @@ export async function awardTicket(
         status: bidData.status 
       });
 
+      // The awarded assets come from the inventory pages holding the next unawarded tickets
+      const eventData = await programClient.fetchEvent(connection.rpc, params.event);
+      const firstPage = Math.floor(eventData.data.assetsAwarded / TICKETS_PER_INVENTORY_PAGE);
+      const lastPage = Math.floor((eventData.data.assetsAwarded + (params.quantity ?? 1) - 1) / TICKETS_PER_INVENTORY_PAGE);
+
       // Create the award ticket instruction with proper string conversions
       console.log("Creating award ticket instruction...");
       const awardTicketIx = await programClient.getAwardTicketInstructionAsync({
@@ -455,7 +502,9 @@ export async function awardTicket(
         logWrapper: params.logWrapper,
         compressionProgram: params.compressionProgram,
         noopProgram: params.noopProgram,
-        cnftAssetId: params.cnftAssetId,
+        inventoryPage: getInventoryPageAddress(params.event, firstPage),
+        nextInventoryPage: lastPage > firstPage ? getInventoryPageAddress(params.event, lastPage) : undefined,
+        quantity: params.quantity ?? 1,
       });
       
       // Send the transaction
@@ -517,6 +566,7 @@ export async function finalizeAuction(
     organizer: KeyPairSigner;
     event: Address;
     closePrice: bigint;
+    tierClosePrices?: bigint[];
   }
 ) {
   // Create the finalize auction instruction
@@ -524,6 +574,7 @@ export async function finalizeAuction(
     organizer: params.organizer,
     event: params.event,
     closePrice: params.closePrice,
+    tierClosePrices: params.tierClosePrices ?? [],
   });
 
   // Send the transaction
@@ -544,7 +595,69 @@ export const ERROR_CODES = {
   AUCTION_NOT_ACTIVE: "AuctionNotActive: custom program error: 0x1771",
   AUCTION_NOT_STARTED: "AuctionNotStarted: custom program error: 0x1772",
   AUCTION_ENDED: "AuctionEnded: custom program error: 0x1773",
-  BID_NOT_AT_CURRENT_PRICE: "BidNotAtCurrentPrice: custom program error: 0x1774",
+  CURRENT_PRICE_ABOVE_MAX: "CurrentPriceAboveMax: custom program error: 0x1774",
+  MIN_TICKETS_EXCEEDS_SUPPLY: "MinTicketsExceedsSupply: custom program error: 0x1775",
+  PAYMENT_ACCOUNTS_MISSING: "PaymentAccountsMissing: custom program error: 0x1776",
+  PAYMENT_MINT_MISMATCH: "PaymentMintMismatch: custom program error: 0x1777",
+  AUCTION_NOT_FINALIZED: "AuctionNotFinalized: custom program error: 0x1778",
+  PROCEEDS_ALREADY_WITHDRAWN: "ProceedsAlreadyWithdrawn: custom program error: 0x1779",
+  INVALID_BID_QUANTITY: "InvalidBidQuantity: custom program error: 0x177a",
+  AWARD_QUANTITY_EXCEEDS_BID: "AwardQuantityExceedsBid: custom program error: 0x177b",
+  WALLET_TICKET_CAP_EXCEEDED: "WalletTicketCapExceeded: custom program error: 0x177c",
+  USER_ACCOUNT_REQUIRED: "UserAccountRequired: custom program error: 0x177d",
+  USER_HOLDINGS_FULL: "UserHoldingsFull: custom program error: 0x177e",
+  INVALID_TICKET_TIER: "InvalidTicketTier: custom program error: 0x177f",
+  TOO_MANY_TICKET_TIERS: "TooManyTicketTiers: custom program error: 0x1780",
+  TIER_CLOSE_PRICES_MISMATCH: "TierClosePricesMismatch: custom program error: 0x1781",
+  METADATA_URL_TOO_LONG: "MetadataUrlTooLong: custom program error: 0x1782",
+  EVENT_ALREADY_ACTIVATED: "EventAlreadyActivated: custom program error: 0x1783",
+  EVENT_NOT_DUE_FOR_ACTIVATION: "EventNotDueForActivation: custom program error: 0x1784",
+  INVALID_EXTENSION_CONFIG: "InvalidExtensionConfig: custom program error: 0x1785",
+  INVALID_INVENTORY_PAGE: "InvalidInventoryPage: custom program error: 0x1786",
+  INVALID_ASSET_REGISTRATION: "InvalidAssetRegistration: custom program error: 0x1787",
+  TICKET_ASSETS_NOT_REGISTERED: "TicketAssetsNotRegistered: custom program error: 0x1788",
+  INVALID_PAYEE_SHARES: "InvalidPayeeShares: custom program error: 0x1789",
+  PROCEEDS_SPLIT_BETWEEN_PAYEES: "ProceedsSplitBetweenPayees: custom program error: 0x178a",
+  PAYEE_ACCOUNT_MISMATCH: "PayeeAccountMismatch: custom program error: 0x178b",
+  BID_NOT_SETTLED: "BidNotSettled: custom program error: 0x178c",
+  EVENT_NOT_SETTLED: "EventNotSettled: custom program error: 0x178d",
+  UNIFORM_PRICE_NOT_ENABLED: "UniformPriceNotEnabled: custom program error: 0x178e",
+  WRONG_AUCTION_TYPE: "WrongAuctionType: custom program error: 0x178f",
+  SEALED_BID_PHASE_MISMATCH: "SealedBidPhaseMismatch: custom program error: 0x1790",
+  COMMITMENT_MISMATCH: "CommitmentMismatch: custom program error: 0x1791",
+  INVALID_SEALED_BID_CONFIG: "InvalidSealedBidConfig: custom program error: 0x1792",
+  INVALID_RANDOMNESS_ACCOUNT: "InvalidRandomnessAccount: custom program error: 0x1793",
+  RANDOMNESS_NOT_RESOLVED: "RandomnessNotResolved: custom program error: 0x1794",
+  LOTTERY_ENTRIES_MISMATCH: "LotteryEntriesMismatch: custom program error: 0x1795",
+  NOT_LOTTERY_WINNER: "NotLotteryWinner: custom program error: 0x1796",
+  TICKET_ALREADY_ISSUED: "TicketAlreadyIssued: custom program error: 0x1797",
+  INVALID_AWARD_BATCH: "InvalidAwardBatch: custom program error: 0x1798",
+  SEQUENTIAL_AWARDS_REQUIRED: "SequentialAwardsRequired: custom program error: 0x1799",
+  SEQUENTIAL_AWARDS_NOT_ENABLED: "SequentialAwardsNotEnabled: custom program error: 0x179a",
+  BID_OUT_OF_SEQUENCE: "BidOutOfSequence: custom program error: 0x179b",
+  BID_STILL_AWARDABLE: "BidStillAwardable: custom program error: 0x179c",
+  WAITLIST_EMPTY: "WaitlistEmpty: custom program error: 0x179d",
+  WAITLIST_OUT_OF_ORDER: "WaitlistOutOfOrder: custom program error: 0x179e",
+  WAITLIST_BID_TOO_SMALL: "WaitlistBidTooSmall: custom program error: 0x179f",
+  TICKETS_SOLD_OUT: "TicketsSoldOut: custom program error: 0x17a0",
+  TICKET_ACCOUNTS_MISSING: "TicketAccountsMissing: custom program error: 0x17a1",
+  CONFLICTING_AWARD_MODES: "ConflictingAwardModes: custom program error: 0x17a2",
+  INVALID_LIMIT_PRICE: "InvalidLimitPrice: custom program error: 0x17a3",
+  NOT_LIMIT_BID: "NotLimitBid: custom program error: 0x17a4",
+  BID_NOT_WITHDRAWABLE: "BidNotWithdrawable: custom program error: 0x17a5",
+  WITHDRAWALS_CLOSED: "WithdrawalsClosed: custom program error: 0x17a6",
+  INVALID_WITHDRAWAL_POLICY: "InvalidWithdrawalPolicy: custom program error: 0x17a7",
+  ACCOUNT_RELATIONSHIP_MISMATCH: "AccountRelationshipMismatch: custom program error: 0x17a8",
+  TICKET_NOT_TRANSFERABLE: "TicketNotTransferable: custom program error: 0x17a9",
+  RESALE_PRICE_ABOVE_CAP: "ResalePriceAboveCap: custom program error: 0x17aa",
+  INVALID_TRANSFER_POLICY: "InvalidTransferPolicy: custom program error: 0x17ab",
+  LISTING_PRICE_ABOVE_MAX: "ListingPriceAboveMax: custom program error: 0x17ac",
+  CLOSE_PRICE_ABOVE_WINNING_BID: "ClosePriceAboveWinningBid: custom program error: 0x17ad",
+  SUPPLY_BELOW_ISSUED_TICKETS: "SupplyBelowIssuedTickets: custom program error: 0x17ae",
+  TICKETS_OUTSTANDING: "TicketsOutstanding: custom program error: 0x17af",
+  SEALED_BID_ENTRIES_MISMATCH: "SealedBidEntriesMismatch: custom program error: 0x17b0",
+  NOT_SEALED_BID_WINNER: "NotSealedBidWinner: custom program error: 0x17b1",
+  TICKET_CHANGED_HANDS: "TicketChangedHands: custom program error: 0x17b2",
 };
 
 // Program-specific event status constants
@@ -552,6 +665,7 @@ export const EVENT_STATUS = {
   CREATED: 0,
   ACTIVE: 1,
   FINALIZED: 2,
+  FAILED: 4,
 };
 
 // Program-specific bid status constants
@@ -559,4 +673,7 @@ export const BID_STATUS = {
   PENDING: 0,
   TICKET_AWARDED: 1,
   REFUNDED: 2,
+  COMMITTED: 3,
+  WAITLISTED: 4,
+  LIMIT: 5,
 };
\ No newline at end of file